@@ -0,0 +1,138 @@
+// Argumentos da linha de comando e o modo sem janela (headless)
+use std::error::Error;
+use std::time::Instant;
+
+use aula1_rust::engine::camera::{Projection, RenderMode, SamplePattern};
+use aula1_rust::engine::Denoiser;
+use aula1_rust::scenes;
+use aula1_rust::utils::{save_hdr_as_pfm, save_image_as_ppm};
+
+pub const USAGE: &str = "\
+Uso: aula1_rust [opções]
+
+Sem opções, abre a janela interativa com a cena `beach`.
+
+Opções:
+  --headless               renderiza sem abrir janela, salva a imagem e sai
+  --scene <nome>           cena a renderizar (beach, cone_test, cilinder_test, sphere_test, simple, cube)
+  --resolution <LxA>       resolução da imagem, ex: 1920x1080 (padrão: a da cena)
+  --projection <tipo>      perspective, orthographic ou oblique (padrão: perspective)
+  --mode <modo>            whitted, path ou ao (padrão: whitted)
+  --samples <n>            amostras por pixel em cada frame, arredondado pra um quadrado perfeito (padrão: 1)
+  --iterations <n>         número de frames acumulados na imagem (padrão: 1)
+  --denoise                aplica o denoiser na imagem final
+  --output <arquivo>       imagem de saída, .ppm ou .pfm (HDR) (padrão: output.ppm)
+  -h, --help               mostra essa mensagem";
+
+/// Opções escolhidas na linha de comando
+pub struct Options {
+    pub help: bool,
+    pub headless: bool,
+    pub scene: String,
+    pub resolution: Option<(u32, u32)>,
+    pub projection: Option<Projection>,
+    pub render_mode: Option<RenderMode>,
+    pub samples: Option<u32>,
+    pub iterations: u32,
+    pub denoise: bool,
+    pub output: String,
+}
+
+/// Lê as opções dos argumentos `args` (sem o nome do programa)
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        help: false,
+        headless: false,
+        scene: "beach".to_string(),
+        resolution: None,
+        projection: None,
+        render_mode: None,
+        samples: None,
+        iterations: 1,
+        denoise: false,
+        output: "output.ppm".to_string(),
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // valor da opção atual (o próximo argumento)
+        let mut value = || args.next().ok_or(format!("faltou o valor de {arg}"));
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "--headless" => options.headless = true,
+            "--denoise" => options.denoise = true,
+            "--scene" => {
+                let scene = value()?;
+                if !scenes::NAMES.contains(&scene.as_str()) { return Err(format!("cena desconhecida: {scene}")); }
+                options.scene = scene.clone();
+            }
+            "--resolution" => {
+                let resolution = value()?;
+                let parsed = resolution.split_once('x')
+                    .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                    .filter(|&(w, h)| w > 0 && h > 0);
+                options.resolution = Some(parsed.ok_or(format!("resolução inválida: {resolution} (use LxA, ex: 1920x1080)"))?);
+            }
+            "--projection" => {
+                options.projection = Some(match value()?.as_str() {
+                    "perspective" => Projection::Perspective,
+                    "orthographic" => Projection::Ortographic,
+                    "oblique" => Projection::Oblique,
+                    other => return Err(format!("projeção desconhecida: {other}")),
+                });
+            }
+            "--mode" => {
+                options.render_mode = Some(match value()?.as_str() {
+                    "whitted" => RenderMode::Whitted,
+                    "path" => RenderMode::PathTracing,
+                    "ao" => RenderMode::AmbientOcclusion,
+                    other => return Err(format!("modo de renderização desconhecido: {other}")),
+                });
+            }
+            "--samples" => {
+                let samples = value()?;
+                options.samples = Some(samples.parse().map_err(|_| format!("número de amostras inválido: {samples}"))?);
+            }
+            "--iterations" => {
+                let iterations = value()?;
+                options.iterations = iterations.parse().ok().filter(|&n| n > 0)
+                    .ok_or(format!("número de iterações inválido: {iterations}"))?;
+            }
+            "--output" => options.output = value()?.clone(),
+            other => return Err(format!("opção desconhecida: {other}")),
+        }
+    }
+    Ok(options)
+}
+
+/// Renderiza a cena escolhida sem abrir nenhuma janela e salva a imagem em `options.output`
+pub fn run_headless(options: &Options) -> Result<(), Box<dyn Error>> {
+    let (scene, mut camera, _, _) = scenes::by_name(&options.scene).ok_or("cena desconhecida")?;
+    if let Some((cols, rows)) = options.resolution { camera.set_resolution(cols, rows); }
+    if let Some(projection) = options.projection { camera.set_projection(projection); }
+    if let Some(render_mode) = options.render_mode { camera.set_render_mode(render_mode); }
+    if let Some(samples) = options.samples {
+        // a grade de amostras é quadrada
+        camera.samples_per_pixel = SamplePattern::sample_count(samples);
+        if camera.samples_per_pixel != samples { println!("usando {} amostras por pixel", camera.samples_per_pixel); }
+    }
+    // cada iteração soma um frame na imagem
+    camera.progressive_refinement = options.iterations > 1;
+    camera.max_refinement_frames = options.iterations;
+
+    let start = Instant::now();
+    for i in 1..=options.iterations {
+        // o denoiser só precisa filtrar a imagem final
+        if i == options.iterations && options.denoise { camera.denoiser = Some(Denoiser::default()); }
+        camera.draw_scene(&scene);
+        println!("frame {i}/{} ({:.2?})", options.iterations, start.elapsed());
+    }
+
+    if options.output.to_lowercase().ends_with(".pfm") {
+        save_hdr_as_pfm(&camera.hdr_image(), &options.output)?;
+    } else {
+        save_image_as_ppm(&camera.image, &options.output)?;
+    }
+    println!("imagem salva em {}", options.output);
+    Ok(())
+}
@@ -1,34 +1,147 @@
 #![allow(dead_code)]
-use super::{Ray, Scene};
-use super::Light;
+use super::{AuxBuffers, Denoiser, Ray, RenderPass, Scene, ToneMapping};
 use crate::utils::transform::rotation_around_axis;
-use crate::utils::Vec3;
-use sdl2::surface::Surface;
+use crate::utils::{ImageF32, ImageRgba8, Rgba8, Rng, ThreadPool, Vec3};
 // use sdl2::rect::Rect;
 // use sdl2::render::Canvas;
 // use sdl2::surface::Surface;
 // use sdl2::video::Window;
-use std::{ptr, thread};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum Projection {
     Perspective,
     Ortographic,
     Oblique
 }
 
-pub struct Camera<'a> {
+#[derive(Clone, Copy, PartialEq)]
+/// Modo de renderização \
+/// `Whitted`: ray tracing clássico (Phong + luz ambiente + reflexões/refrações), rápido pra pré-visualizar \
+/// `PathTracing`: iluminação global por Monte Carlo, progressiva (acumula amostras entre frames) \
+/// `AmbientOcclusion`: debug, mostra só a oclusão ambiente da cena
+pub enum RenderMode {
+    Whitted,
+    PathTracing,
+    AmbientOcclusion,
+}
+
+impl RenderMode {
+    pub const ALL: [RenderMode; 3] = [RenderMode::Whitted, RenderMode::PathTracing, RenderMode::AmbientOcclusion];
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderMode::Whitted => "Whitted (Phong)",
+            RenderMode::PathTracing => "Path tracing",
+            RenderMode::AmbientOcclusion => "Ambient occlusion (debug)",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+/// Padrão de distribuição das amostras dentro de cada pixel (anti-aliasing) \
+/// `Grid`: grade regular \
+/// `Jittered`: grade com cada amostra sorteada dentro da sua célula (estratificada) \
+/// `RotatedGrid`: grade regular girada (atan(1/2)), boa pra bordas quase horizontais/verticais
+pub enum SamplePattern {
+    Grid,
+    Jittered,
+    RotatedGrid,
+}
+
+impl SamplePattern {
+    pub const ALL: [SamplePattern; 3] = [SamplePattern::Grid, SamplePattern::Jittered, SamplePattern::RotatedGrid];
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            SamplePattern::Grid => "Grid",
+            SamplePattern::Jittered => "Jittered",
+            SamplePattern::RotatedGrid => "Rotated grid",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Número de amostras que `offsets` gera pra `n` amostras pedidas
+    /// (`n` arredondado pra cima pro próximo quadrado perfeito, no mínimo 1)
+    pub fn sample_count(n: u32) -> u32 {
+        grid_size(n).pow(2)
+    }
+
+    /// Preenche `out` com as posições das amostras dentro de um pixel, relativas ao centro dele
+    /// (entre -0.5 e 0.5, em unidades de pixel). \
+    /// `n` é arredondado pra cima pro próximo quadrado perfeito (ver `sample_count`).
+    pub fn offsets(&self, n: u32, rng: &mut Rng, out: &mut Vec<(f64, f64)>) {
+        out.clear();
+        let m = grid_size(n);
+        if m == 1 && *self != SamplePattern::Jittered {
+            out.push((0.0, 0.0)); // 1 amostra: centro do pixel
+            return;
+        }
+
+        let step = 1.0 / m as f64;
+        for i in 0..m {
+            for j in 0..m {
+                // canto da célula (i, j) da grade
+                let (cx, cy) = (j as f64 * step, i as f64 * step);
+                let (x, y) = match self {
+                    SamplePattern::Grid => (cx + step/2.0, cy + step/2.0),
+                    SamplePattern::Jittered => (cx + rng.next_f64()*step, cy + rng.next_f64()*step),
+                    SamplePattern::RotatedGrid => {
+                        // gira a grade em torno do centro do pixel e "dá a volta" no que sair dele
+                        let (px, py) = (cx + step/2.0 - 0.5, cy + step/2.0 - 0.5);
+                        let (sin, cos) = 0.5_f64.atan().sin_cos();
+                        let (rx, ry) = (px*cos - py*sin + 0.5, px*sin + py*cos + 0.5);
+                        (rx - rx.floor(), ry - ry.floor())
+                    }
+                };
+                out.push((x - 0.5, y - 0.5));
+            }
+        }
+    }
+}
+
+// lado da grade de amostras usada pra `n` amostras por pixel (a menor grade com pelo menos `n` amostras)
+fn grid_size(n: u32) -> u32 {
+    ((n as f64).sqrt().ceil() as u32).max(1)
+}
+
+pub struct Camera {
     pub pos: Vec3, // observador
     pub coord_system: [Vec3; 3],
     pub focal_distance: f64,
     pub projection_type: Projection,
     pub obliqueness: Vec3,
     pub viewport: Viewport, // janela   
-    pub sdl_surface: Surface<'a>,
+    pub max_depth: u32, // número máximo de reflexões seguidas por raio
+    pub max_bounces: u32, // número máximo de quiques de cada caminho no path tracing
+    pub samples_per_pixel: u32, // número de amostras por pixel (anti-aliasing)
+    pub sample_pattern: SamplePattern,
+    pub adaptive_aa: bool, // anti-aliasing adaptativo (só refina os pixels com muito contraste)
+    pub contrast_threshold: f64, // diferença de cor a partir da qual um pixel é refinado
+    pub max_subdivision: u32, // número máximo de subdivisões de um pixel no modo adaptativo
+    pub render_mode: RenderMode,
+    pub aperture: f64, // raio da lente (0 = câmera pinhole, tudo em foco)
+    pub focus_distance: f64, // distância (no eixo de visada) do plano que fica em foco
+    pub progressive_refinement: bool, // continua somando amostras sorteadas na imagem enquanto nada muda
+    pub max_refinement_frames: u32, // número de frames acumulados a partir do qual a câmera para de renderizar (0 = sem limite)
+    accumulation: Accumulator, // amostras somadas pelo path tracing / refinamento progressivo
+    pub accumulated_frames: u32, // número de frames somados na imagem mostrada
+    hdr_buffer: Vec<Vec3>, // cores do último frame, antes do tone mapping
+    denoised_buffer: Option<Vec<Vec3>>, // `hdr_buffer` filtrado pelo `denoiser` (None = ainda não filtrado)
+    pub tone_mapping: ToneMapping,
+    pub exposure: f64, // exposição em stops (cada +1 dobra o brilho da imagem)
+    aux_buffers: Arc<AuxBuffers>, // passes auxiliares (profundidade, normal, etc.) do último frame
+    pub render_pass: RenderPass, // passe mostrado na imagem da câmera
+    pub denoiser: Option<Denoiser>, // filtro de ruído aplicado em cada frame (None = imagem sem filtro)
+    pub image: ImageRgba8, // imagem final (sRGB, 8 bits), com o passe selecionado em `render_pass`
 }
 
-impl <'a> Camera<'a> {
+impl Camera {
     #[inline]
     #[must_use]
     /// Cria uma nova câmera. \
@@ -37,18 +150,36 @@ impl <'a> Camera<'a> {
     /// `viewport_w`, `viewport_h`: Tamanho do viewport em metros \
     /// `viewport_distance`: Distância do viewport até o observador \
     /// `bg_color`: Cor do background
-    /// `texture_creator`: TextureCreator para criar a textura interna de buffer da câmera
-    pub fn new(pos: Vec3, n_cols: u32, n_rows: u32, viewport_w: f64, viewport_h: f64, focal_distance: f64) -> Camera<'a> {     
-        let sdl_surface = Surface::new(n_cols, n_rows, sdl2::pixels::PixelFormatEnum::RGB888).unwrap();
-
+    pub fn new(pos: Vec3, n_cols: u32, n_rows: u32, viewport_w: f64, viewport_h: f64, focal_distance: f64) -> Camera {     
         Camera {
             pos, // posição do observador
             focal_distance,
             coord_system: [Vec3::X, Vec3::Y, Vec3::Z],
             projection_type: Projection::Perspective,
             obliqueness: Vec3::new(0.0, 30.0, 0.0),
+            max_depth: 3,
+            max_bounces: 16,
+            samples_per_pixel: 1,
+            sample_pattern: SamplePattern::Grid,
+            adaptive_aa: false,
+            contrast_threshold: 0.1,
+            max_subdivision: 2,
+            render_mode: RenderMode::Whitted,
+            aperture: 0.0,
+            focus_distance: focal_distance,
+            progressive_refinement: true,
+            max_refinement_frames: 256,
+            accumulation: Accumulator::new((n_cols * n_rows) as usize),
+            accumulated_frames: 0,
+            hdr_buffer: vec![Vec3::NULL; (n_cols * n_rows) as usize],
+            denoised_buffer: None,
+            tone_mapping: ToneMapping::Clamp,
+            exposure: 0.0,
+            aux_buffers: Arc::new(AuxBuffers::default()),
+            render_pass: RenderPass::Beauty,
+            denoiser: None,
             
-            sdl_surface,
+            image: ImageRgba8::new(n_cols, n_rows),
 
             viewport: Viewport::new(
                 Vec3::new(pos.x, pos.y, pos.z-focal_distance), // posição da janela em relação ao observador (0, 0, -d)
@@ -59,7 +190,8 @@ impl <'a> Camera<'a> {
     }
 
     pub fn set_resolution(&mut self, cols: u32, rows: u32) {
-        self.sdl_surface = Surface::new(cols, rows, sdl2::pixels::PixelFormatEnum::RGB888).unwrap();
+        self.image = ImageRgba8::new(cols, rows);
+        self.hdr_buffer = vec![Vec3::NULL; (cols * rows) as usize];
         self.viewport = Viewport::new(
             Vec3::new(0.0, 0.0, -self.focal_distance), // posição da janela em relação ao observador (0, 0, -d)
             self.viewport.width, self.viewport.height, // altura * largura da janela
@@ -70,9 +202,11 @@ impl <'a> Camera<'a> {
         self.viewport.pos = self.camera_to_world(self.viewport.pos);
         self.viewport.dx = self.coord_system[0] * self.viewport.dx.length();
         self.viewport.dy = self.coord_system[1] * self.viewport.dy.length();
+        self.reset_accumulation();
     }
 
     pub fn set_viewport_size(&mut self, width: f64, height: f64) {
+        self.reset_accumulation();
         self.viewport = Viewport::new(
             Vec3::new(0.0, 0.0, -self.focal_distance), // posição da janela em relação ao observador (0, 0, -d)
             width, height, // altura * largura da janela
@@ -103,6 +237,7 @@ impl <'a> Camera<'a> {
 
     
     pub fn set_focal_distance(&mut self, focal_distance: f64) {
+        self.reset_accumulation();
         self.focal_distance = focal_distance;
         self.viewport = Viewport::new(
             Vec3::new(0.0, 0.0, -focal_distance), // posição da janela em relação ao observador (0, 0, -d)
@@ -116,204 +251,216 @@ impl <'a> Camera<'a> {
         self.viewport.dy = self.coord_system[1] * self.viewport.dy.length();
     }
 
-    /// Desenha uma cena em um canvas com base nas especificações da câmera
-    pub fn draw_scene(&mut self, scene: &Scene) {
-        // Número de bytes no canvas (número de pixels * 3: RGB24)
-        let num_bytes = self.viewport.cols * self.viewport.rows * 4;
-        // Número de threads disponíveis * 3
-        // (Nos meus testes usar o triplo de threads disponíveis tende a aumentar a eficiência por algum motivo)
-        let num_threads = thread::available_parallelism().unwrap().get() as u32 * 3; 
-        
-        // Referências thread-safe
-        let scene = Arc::new(scene); // Cena
-        let viewport = Arc::new(&self.viewport); // Viewport da câmera
-        
-        // Render multithread
-        // (A câmera tem um array de pixels em formato RGB24. A gente divide esse buffer pra várias threads
-        // e elas vão calcular os pixels em paralelo, acelerando o render.)
-        let surface_pixels = self.sdl_surface.without_lock_mut().unwrap();
-        thread::scope(|s| {
-        let mut lower_bound = 0;
-        // Divide o array de buffer em chunks de tamanhos iguais pras threads
-        for ppm_slice in surface_pixels.chunks_mut((num_bytes/num_threads) as usize) {
-            // Clona as referências pesadas e os vetores leves para serem movidos para outra thread
-            let scene = Arc::clone(&scene);
-            let viewport = Arc::clone(&viewport);
-            let self_pos = self.pos;
-            let bg_color = scene.bg_color;
-            let projection_type = self.projection_type;
-            let coord_system = self.coord_system;
-            let obliqueness = self.obliqueness;
-
-            // Número de pixels que a thread vai desenhar
-            let pixel_count = ppm_slice.len() / 4;
-            
-            s.spawn(move || {
-                let mut ray = match projection_type {
-                    Projection::Perspective => {
-                        Ray::new(self_pos, Vec3::NULL) // cria um raio partindo de p0 na direção d
-                    }
-                    Projection::Ortographic => {
-                        Ray::new(viewport.p00, -coord_system[2])
-                    }
-                    Projection::Oblique => {
-                        let mut dr = -coord_system[2];
-                        if obliqueness.x != 0.0 {
-                            dr.transform(&rotation_around_axis(coord_system[0], obliqueness.x.to_radians(), Vec3::NULL));
-                        }
-                        if obliqueness.y != 0.0 {
-                            dr.transform(&rotation_around_axis(coord_system[1], obliqueness.y.to_radians(), Vec3::NULL));
-                        }
-                        if obliqueness.z != 0.0 {
-                            dr.transform(&rotation_around_axis(coord_system[2], obliqueness.z.to_radians(), Vec3::NULL));
-                        }
-                        Ray::new(viewport.p00, dr)
-                    }
-                };
-                let mut rgb_counter = 0;
-                
-                for pixel in 0..pixel_count {
-                    let row = (lower_bound + pixel) / (viewport.cols as usize);
-                    let col = (lower_bound + pixel) % (viewport.cols as usize);
-                    match projection_type {
-                        Projection::Perspective => {
-                            ray.dr = (((viewport.p00) + (col as f64)*viewport.dx - (row as f64)*viewport.dy) - self_pos).normalized();
-                        }
-                        Projection::Ortographic => {
-                            ray.origin = (viewport.p00) + (col as f64)*viewport.dx - (row as f64)*viewport.dy;
-                        }
-                        Projection::Oblique => {
-                            ray.origin = (viewport.p00) + (col as f64)*viewport.dx - (row as f64)*viewport.dy;
-                        }
-                    };
+    /// Descarta as amostras acumuladas e marca a imagem como desatualizada
+    /// (tem que ser chamado sempre que a câmera ou a cena mudam)
+    pub fn reset_accumulation(&mut self) {
+        self.accumulation = Accumulator::new((self.viewport.cols * self.viewport.rows) as usize);
+        self.accumulated_frames = 0;
+        self.denoised_buffer = None;
+    }
 
-                    // Obtém o objeto mais próximo a colidir com o raio
-                    let intersection = scene.get_intersection(&ray);
-
-                    // se o raio não colide com nenhum objeto, desenha a cor do background e passa pro próximo pixel
-                    if intersection.is_none() {
-                        ppm_slice[rgb_counter] = bg_color.z as u8;
-                        ppm_slice[rgb_counter + 1] = bg_color.y as u8;
-                        ppm_slice[rgb_counter + 2] = bg_color.x as u8;
-                        ppm_slice[rgb_counter + 3] = 255;
-                        rgb_counter += 4;
-                        continue;
-                    }
-                    
-                    let (shape, t, n, mat) = intersection.unwrap();
-                    // mat = shape.material(); // material do objeto
-                    
-                    // Calcula a cor do pixel de acordo com a iluminação
-                    // intensidade da luz que chega no olho do observador (começa com a luz ambiente)
-                    let mut ieye = mat.k_amb * scene.ambient_light;
-                    let p_i = ray.at(t); // ponto de interseção
-                    'lights: for light in &scene.lights {
-                        let ldr: Vec3;
-                        let light_intensity: Vec3;
-                        let mut is_directional= false;
-                        match light {
-                            Light::Point { pos, intensity } => {
-                                ldr = *pos - p_i;
-                                light_intensity = *intensity;
-                            }
-                            Light::Spotlight { pos, dr, angle, intensity } => {
-                                ldr = *pos - p_i;
-                                light_intensity = *intensity;
-                                if dr.dot(ldr.normalized()) <= angle.to_radians().cos() { continue 'lights; }
-                            }
-                            Light::Directional { dr, intensity } => {
-                                ldr = *dr;
-                                light_intensity = *intensity;
-                                is_directional = true;
-                            }
-                        }
-
-                        // Checar se o objeto está na sombra de algum outro objeto
-                        let light_ray = Ray::new(p_i, ldr); // raio partindo de p_i até o ponto de luz
-                        for s in &scene.shapes {
-                            // Tem alguns problemas de iluminação com detecção de colisão consigo mesmo. Não sei ajeitar ainda.
-                            if ptr::eq(s, shape) { continue; }
-
-                            // se tem um objeto ENTRE p_i e a luz (não está atrás da luz ou atrás de p_i (0.0 < tl < 1.0))
-                            // 0.0001 previne problemas com floating point precision
-                            if let Some((tl, _, _)) = s.get_intersection(&light_ray) {
-                                if 0.0001 < tl && (is_directional || tl < 0.9999) { continue 'lights; }
-                            }
-                        }
-                        
-                        // Se o objeto não estiver na sombra...
-                        let l = light_ray.dr.normalized(); // vetor unitário apontando na direção da luz
-                    
-                        let r = 2.0 * l.dot(n)*n - l; // vetor l refletido na normal
-                        let nl = n.dot(l); // normal escalar l
-                        let rv = r.dot(-ray.dr); // r escalar v
-
-                        // O check > 0.0 previne o bug de iluminação no "lado escuro da esfera"
-                        if nl > 0.0 { ieye += mat.k_dif * nl * light_intensity; } // Reflexão difusa
-                        if rv > 0.0 { ieye += mat.k_esp * rv.powf(mat.e) * light_intensity } // Reflexão especular
-                    }
-                    
-                    // converte pra range de u8, etc.
-                    ieye = ieye.clamp(0.0, 1.0) * 255.0;
-                    
-                    // salva o pixel no buffer da câmera
-                    ppm_slice[rgb_counter] = ieye.z as u8;
-                    ppm_slice[rgb_counter + 1] = ieye.y as u8;
-                    ppm_slice[rgb_counter + 2] = ieye.x as u8;
-                    ppm_slice[rgb_counter + 3] = 255;
-                    rgb_counter += 4;
-                    
-                }
-            });
+    #[inline]
+    #[must_use]
+    /// Se a câmera ou a cena mudaram desde o último frame renderizado
+    pub fn is_dirty(&self) -> bool {
+        self.accumulation.frames == 0
+    }
 
-            lower_bound += pixel_count;
-        }
-        });
+    #[must_use]
+    /// Se a imagem já acumulou todos os frames que devia, e não precisa ser renderizada de novo
+    /// até a câmera ou a cena mudarem
+    pub fn is_converged(&self) -> bool {
+        self.snapshot().is_converged(&self.accumulation)
     }
 
     #[must_use]
-    /// índice do objeto, ponto de interseção, normal
-    pub fn send_ray(&self, row: i32, col: i32, scene: &Scene) -> Option<(usize, Vec3, Vec3)> {
-        let mut ray = match self.projection_type {
-            Projection::Perspective => {
-                Ray::new(self.pos, Vec3::new(0.0,0.0,1.0)) // cria um raio partindo de p0 na direção d
+    /// Copia os parâmetros da câmera usados pra renderizar
+    pub fn snapshot(&self) -> CameraSnapshot {
+        CameraSnapshot {
+            base_ray: self.base_ray(),
+            pos: self.pos,
+            coord_system: self.coord_system,
+            projection_type: self.projection_type,
+            viewport: self.viewport.clone(),
+            max_depth: self.max_depth,
+            max_bounces: self.max_bounces,
+            samples_per_pixel: self.samples_per_pixel,
+            sample_pattern: self.sample_pattern,
+            adaptive_aa: self.adaptive_aa,
+            contrast_threshold: self.contrast_threshold,
+            max_subdivision: self.max_subdivision,
+            render_mode: self.render_mode,
+            aperture: self.aperture,
+            focus_distance: self.focus_distance,
+            progressive_refinement: self.progressive_refinement,
+            max_refinement_frames: self.max_refinement_frames,
+        }
+    }
+
+    /// Mostra uma imagem HDR renderizada em outro lugar (ex: em `BackgroundRenderer`), trocando ela
+    /// com o buffer HDR da câmera, e atualiza a imagem de 8 bits. \
+    /// `aux_buffers`: passes auxiliares da imagem \
+    /// `frames`: número de frames acumulados nessa imagem
+    pub fn show_frame(&mut self, hdr_buffer: &mut Vec<Vec3>, aux_buffers: Arc<AuxBuffers>, frames: u32) {
+        std::mem::swap(&mut self.hdr_buffer, hdr_buffer);
+        self.aux_buffers = aux_buffers;
+        // as amostras dessa imagem não estão no acumulador da câmera (que fica como está, pra não
+        // alocar um acumulador novo a cada frame mostrado)
+        self.accumulated_frames = frames;
+        self.apply_denoiser();
+    }
+
+    /// Filtra o último frame com o `denoiser` atual (ou tira o filtro, se ele foi desligado) e atualiza
+    /// a imagem de 8 bits (não precisa renderizar a cena de novo)
+    pub fn apply_denoiser(&mut self) {
+        self.denoised_buffer = self.denoiser.map(|denoiser| denoiser.apply(&self.hdr_buffer, &self.aux_buffers));
+        self.update_image();
+    }
+
+    pub fn set_render_mode(&mut self, render_mode: RenderMode) {
+        self.render_mode = render_mode;
+        self.reset_accumulation();
+    }
+
+    /// Ajusta a distância de foco pra que o ponto `point` fique nítido
+    pub fn focus_on(&mut self, point: Vec3) {
+        self.focus_distance = (point - self.pos).dot(-self.coord_system[2]);
+        self.reset_accumulation();
+    }
+
+    /// Desenha uma cena em um canvas com base nas especificações da câmera. \
+    /// Se nada mudou desde o último frame, soma mais amostras na imagem (refinamento progressivo),
+    /// ou não faz nada se ela já convergiu.
+    pub fn draw_scene(&mut self, scene: &Scene) {
+        self.draw_scene_with_progress(scene, |_| {});
+    }
+
+    /// Igual a `draw_scene`, mas chama `on_progress` com a imagem parcial de tempos em tempos
+    /// enquanto os blocos da imagem vão ficando prontos (pra mostrar o progresso de frames demorados)
+    pub fn draw_scene_with_progress(&mut self, scene: &Scene, mut on_progress: impl FnMut(&ImageRgba8)) {
+        let num_pixels = (self.viewport.cols * self.viewport.rows) as usize;
+        if self.accumulation.sum.len() != num_pixels { self.reset_accumulation(); }
+        if self.hdr_buffer.len() != num_pixels { self.hdr_buffer = vec![Vec3::NULL; num_pixels]; }
+
+        let snapshot = self.snapshot();
+        // os passes auxiliares não mudam com o refinamento, então só são calculados no primeiro frame
+        if self.accumulation.frames == 0 {
+            self.aux_buffers = Arc::new(snapshot.render_aux(scene, &AtomicBool::new(false)));
+        }
+        let cols = self.viewport.cols as usize;
+        let (tone_mapping, exposure) = (self.tone_mapping, self.exposure);
+        let show_beauty = self.render_pass == RenderPass::Beauty;
+        let image = &mut self.image;
+        let frames_before = self.accumulation.frames;
+        let mut last_progress = Instant::now();
+        // atualiza a imagem da câmera assim que cada bloco fica pronto
+        snapshot.render(scene, &mut self.accumulation, &mut self.hdr_buffer, &AtomicBool::new(false), |tile, hdr_buffer| {
+            if !show_beauty { return; }
+            for pixel in tile.pixels(cols) {
+                image.pixels[pixel] = to_rgba8(hdr_buffer[pixel], tone_mapping, exposure);
             }
-            Projection::Ortographic => {
-                Ray::new(self.viewport.p00, -self.coord_system[2])
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(image);
+                last_progress = Instant::now();
             }
-            Projection::Oblique => {
-                Ray::new(self.viewport.p00, -self.coord_system[2])
+        });
+        self.accumulated_frames = self.accumulation.frames;
+        // o filtro é aplicado numa cópia (as amostras acumuladas continuam sem filtro), e só quando
+        // um frame novo foi renderizado (ou o denoiser acabou de ser ligado)
+        let new_frame = self.accumulation.frames != frames_before;
+        if let Some(denoiser) = &self.denoiser && (new_frame || self.denoised_buffer.is_none()) {
+            self.denoised_buffer = Some(denoiser.apply(&self.hdr_buffer, &self.aux_buffers));
+        }
+        if !show_beauty || self.denoiser.is_some() { self.update_image(); }
+    }
+
+    /// Atualiza a imagem sRGB de 8 bits da câmera com o passe selecionado em `render_pass`: a imagem final
+    /// usa o buffer HDR (linear) com o operador de tone mapping e a exposição atuais, e os passes auxiliares
+    /// usam a visualização de `AuxBuffers::visualize` (não precisa renderizar a cena de novo)
+    pub fn update_image(&mut self) {
+        let show_beauty = self.render_pass == RenderPass::Beauty || self.aux_buffers.depth.len() != self.hdr_buffer.len();
+        if show_beauty {
+            let hdr_buffer = match (&self.denoiser, &self.denoised_buffer) {
+                (Some(_), Some(denoised)) => denoised,
+                _ => &self.hdr_buffer,
+            };
+            for (rgba, color) in self.image.pixels.iter_mut().zip(hdr_buffer) {
+                *rgba = to_rgba8(*color, self.tone_mapping, self.exposure);
             }
-        };
+        } else {
+            for (rgba, color) in self.image.pixels.iter_mut().zip(self.aux_buffers.visualize(self.render_pass)) {
+                *rgba = to_rgba8(color, ToneMapping::Clamp, 0.0);
+            }
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cores HDR (sem tone mapping, sem limite de intensidade) do último frame renderizado, linha por linha
+    /// (filtradas, se a câmera tiver um `denoiser`)
+    pub fn hdr_buffer(&self) -> &[Vec3] {
+        self.output_buffer()
+    }
+
+    #[must_use]
+    /// Cópia do buffer HDR como uma imagem de floats de 32 bits (ex: pra salvar como .pfm)
+    pub fn hdr_image(&self) -> ImageF32 {
+        ImageF32::from_colors(self.viewport.cols, self.viewport.rows, self.output_buffer())
+    }
 
+    // Imagem HDR mostrada: a filtrada pelo denoiser, se ele estiver ligado e já tiver filtrado o último frame
+    fn output_buffer(&self) -> &[Vec3] {
+        match (&self.denoiser, &self.denoised_buffer) {
+            (Some(_), Some(denoised)) => denoised,
+            _ => &self.hdr_buffer,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Passes auxiliares (profundidade, normal, albedo e índice dos objetos) do último frame renderizado
+    pub fn aux_buffers(&self) -> &AuxBuffers {
+        &self.aux_buffers
+    }
+
+    #[must_use]
+    /// Raio "base" da projeção da câmera, que depois é ajustado pra cada pixel com `aim_ray`
+    fn base_ray(&self) -> Ray {
         match self.projection_type {
             Projection::Perspective => {
-                ray.dr = (((self.viewport.p00) + (col as f64)*self.viewport.dx - (row as f64)*self.viewport.dy) - self.pos).normalized();
+                Ray::new(self.pos, Vec3::NULL) // cria um raio partindo de p0 na direção d
             }
             Projection::Ortographic => {
-                ray.origin = (self.viewport.p00) + (col as f64)*self.viewport.dx - (row as f64)*self.viewport.dy;
+                Ray::new(self.viewport.p00, -self.coord_system[2])
             }
             Projection::Oblique => {
-                ray.origin = (self.viewport.p00) + (col as f64)*self.viewport.dx - (row as f64)*self.viewport.dy;
-            }
-        };
-
-        let intersection = scene.get_intersection(&ray);
-        match intersection {
-            None => { None }
-            Some( (shape, t, normal, _material) )=> {
-                let mut counter: usize = 0;
-                for s in &scene.shapes {
-                    if ptr::eq(s, shape) { break; }
-                    counter += 1;
+                let mut dr = -self.coord_system[2];
+                if self.obliqueness.x != 0.0 {
+                    dr.transform(&rotation_around_axis(self.coord_system[0], self.obliqueness.x.to_radians(), Vec3::NULL));
+                }
+                if self.obliqueness.y != 0.0 {
+                    dr.transform(&rotation_around_axis(self.coord_system[1], self.obliqueness.y.to_radians(), Vec3::NULL));
+                }
+                if self.obliqueness.z != 0.0 {
+                    dr.transform(&rotation_around_axis(self.coord_system[2], self.obliqueness.z.to_radians(), Vec3::NULL));
                 }
-                Some( (counter, ray.at(t), normal) )
+                Ray::new(self.viewport.p00, dr)
             }
         }
+    }
 
+    #[must_use]
+    /// índice do objeto, ponto de interseção, normal
+    pub fn send_ray(&self, row: i32, col: i32, scene: &Scene) -> Option<(usize, Vec3, Vec3)> {
+        let mut ray = self.base_ray();
+        aim_ray(&mut ray, self.projection_type, &self.viewport, self.pos, row as f64, col as f64);
+
+        scene.get_intersection(&ray)
+            .map(|(shape_index, t, normal, _material)| (shape_index, ray.at(t), normal))
     }
 
     pub fn set_projection(&mut self, projection: Projection) {
+        self.reset_accumulation();
         self.projection_type = projection;
     }
 
@@ -322,6 +469,7 @@ impl <'a> Camera<'a> {
     }
 
     pub fn translate(&mut self, translation_vector: Vec3) {
+        self.reset_accumulation();
         self.pos += translation_vector;
         self.viewport.pos += translation_vector;
         self.viewport.p00 += translation_vector;
@@ -329,6 +477,7 @@ impl <'a> Camera<'a> {
     }
 
     pub fn rotate(&mut self, axis: Vec3, angle: f64) {
+        self.reset_accumulation();
         let translation_vector = self.pos;
         let transformation_matrix = rotation_around_axis(axis, angle, Vec3::NULL);
 
@@ -352,6 +501,7 @@ impl <'a> Camera<'a> {
     }
 
     pub fn look_at(&mut self, point: Vec3, mut up: Vec3) {
+        self.reset_accumulation();
         up = (up - self.pos).normalized();
         // Calculate the forward direction (view direction)
         let forward = (point - self.pos).normalized();
@@ -380,6 +530,7 @@ impl <'a> Camera<'a> {
     }
 
     pub fn set_coord_system(&mut self, coord_system: [Vec3; 3]) {
+        self.reset_accumulation();
         self.coord_system = coord_system;
 
         self.viewport = Viewport::new(
@@ -395,6 +546,355 @@ impl <'a> Camera<'a> {
     }
 }
 
+#[derive(Clone, PartialEq)]
+/// Cópia dos parâmetros da câmera usados pra renderizar (ver `Camera::snapshot`). \
+/// É independente da imagem da câmera, então pode ser mandada pra outra thread.
+pub struct CameraSnapshot {
+    base_ray: Ray,
+    pos: Vec3,
+    coord_system: [Vec3; 3],
+    projection_type: Projection,
+    viewport: Viewport,
+    max_depth: u32,
+    max_bounces: u32,
+    samples_per_pixel: u32,
+    sample_pattern: SamplePattern,
+    adaptive_aa: bool,
+    contrast_threshold: f64,
+    max_subdivision: u32,
+    render_mode: RenderMode,
+    aperture: f64,
+    focus_distance: f64,
+    progressive_refinement: bool,
+    max_refinement_frames: u32,
+}
+
+/// Soma das amostras de cada pixel de uma imagem que está sendo refinada progressivamente
+pub struct Accumulator {
+    sum: Vec<Vec3>,
+    samples: u32, // número de amostras por pixel somadas em `sum`
+    pub frames: u32, // número de frames somados em `sum`
+}
+
+impl Accumulator {
+    #[inline]
+    #[must_use]
+    /// Cria um acumulador vazio pra uma imagem de `num_pixels` pixels
+    pub fn new(num_pixels: usize) -> Accumulator {
+        Accumulator { sum: vec![Vec3::NULL; num_pixels], samples: 0, frames: 0 }
+    }
+}
+
+impl CameraSnapshot {
+    #[inline]
+    #[must_use]
+    /// Resolução (colunas, linhas) da imagem
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.viewport.cols, self.viewport.rows)
+    }
+
+    #[must_use]
+    /// Se a imagem em `accumulation` já acumulou todos os frames que devia
+    pub fn is_converged(&self, accumulation: &Accumulator) -> bool {
+        let progressive = self.progressive_refinement || self.render_mode == RenderMode::PathTracing;
+        let frame_limit = if progressive { self.max_refinement_frames } else { 1 };
+        frame_limit > 0 && accumulation.frames >= frame_limit
+    }
+
+    #[must_use]
+    /// Calcula os passes auxiliares (profundidade, normal, albedo e índice dos objetos) com um raio pelo
+    /// centro de cada pixel (se `cancel` virar `true` no meio, os pixels que faltam ficam vazios)
+    pub fn render_aux(&self, scene: &Scene, cancel: &AtomicBool) -> AuxBuffers {
+        let (cols, rows) = self.resolution();
+        let mut pixels = vec![(f64::INFINITY, Vec3::NULL, Vec3::NULL, None); (cols * rows) as usize];
+        render_pixels(&mut pixels, cols as usize, 0, cancel, |pixel, _| {
+            let mut ray = self.base_ray.clone();
+            let (row, col) = ((pixel / cols as usize) as f64, (pixel % cols as usize) as f64);
+            aim_ray(&mut ray, self.projection_type, &self.viewport, self.pos, row, col);
+            match scene.get_intersection(&ray) {
+                None => (f64::INFINITY, Vec3::NULL, scene.sky_color(ray.dr), None),
+                Some((shape_index, t, n, mat)) => {
+                    let n = if n.dot(ray.dr) > 0.0 { -n } else { n };
+                    (t * ray.dr.length(), n.normalized(), mat.k_dif, Some(shape_index))
+                }
+            }
+        });
+
+        let mut aux = AuxBuffers { cols, rows, ..AuxBuffers::default() };
+        for (depth, normal, albedo, shape_index) in pixels {
+            aux.depth.push(depth);
+            aux.normal.push(normal);
+            aux.albedo.push(albedo);
+            aux.shape_index.push(shape_index);
+        }
+        aux
+    }
+
+    /// Renderiza um frame da cena e soma ele em `accumulation`, escrevendo a média em `hdr_buffer`. \
+    /// Se `accumulation` já tem frames, sorteia novas amostras pra refinar a imagem (refinamento progressivo);
+    /// se a imagem já convergiu, não faz nada. \
+    /// `on_tile(bloco, hdr_buffer)` é chamado assim que cada bloco da imagem fica pronto. \
+    /// Se `cancel` virar `true` no meio do frame, os blocos que faltam são abandonados.
+    pub fn render(
+        &self, scene: &Scene, accumulation: &mut Accumulator, hdr_buffer: &mut [Vec3],
+        cancel: &AtomicBool, mut on_tile: impl FnMut(Tile, &[Vec3])
+    ) {
+        let cols = self.viewport.cols as usize;
+        let rows = self.viewport.rows as usize;
+        let num_pixels = cols * rows;
+        if self.is_converged(accumulation) { return; }
+
+        // Copia os parâmetros leves e pega referências do que é pesado pra usar nas threads
+        let base_ray = &self.base_ray;
+        let viewport = &self.viewport;
+        let self_pos = self.pos;
+        let max_depth = self.max_depth;
+        let max_bounces = self.max_bounces;
+        let projection_type = self.projection_type;
+        let samples_per_pixel = self.samples_per_pixel;
+        let sample_pattern = self.sample_pattern;
+        let contrast_threshold = self.contrast_threshold;
+        let max_subdivision = self.max_subdivision;
+        let aperture = self.aperture;
+        let focus_distance = self.focus_distance;
+        let coord_system = self.coord_system;
+
+        // Raio que sai da câmera e passa pelo ponto (row, col) do viewport
+        // (com a origem sorteada na lente, se a câmera tiver abertura)
+        let primary_ray = |row: f64, col: f64, rng: &mut Rng| -> Ray {
+            let mut ray = base_ray.clone();
+            aim_ray(&mut ray, projection_type, viewport, self_pos, row, col);
+            if aperture > 0.0 {
+                apply_lens(&mut ray, aperture, focus_distance, self_pos, coord_system, rng);
+            }
+            ray
+        };
+
+        // Lança um raio pelo ponto (row, col) do viewport e retorna a cor (HDR) e o objeto atingido
+        let ao_only = self.render_mode == RenderMode::AmbientOcclusion;
+        let sample = |row: f64, col: f64, rng: &mut Rng| -> (Vec3, Option<usize>) {
+            let ray = primary_ray(row, col, rng);
+            if ao_only { scene.trace_ambient_occlusion(&ray, rng) } else { scene.trace_hit(&ray, max_depth, rng) }
+        };
+
+        let path_tracing = self.render_mode == RenderMode::PathTracing;
+        // Path tracing, ou refinamento de uma imagem parada: cada frame sorteia novas amostras
+        // dentro de cada pixel, que são somadas no buffer de acumulação
+        let refining = path_tracing || accumulation.frames > 0;
+        let adaptive_aa = self.adaptive_aa;
+        // número de amostras por pixel desse frame (o peso dele na média acumulada)
+        let frame_samples = if refining {
+            samples_per_pixel.max(1)
+        } else if adaptive_aa {
+            1
+        } else {
+            SamplePattern::sample_count(samples_per_pixel)
+        };
+        let frame_seed = accumulation.frames as u64;
+        accumulation.frames += 1;
+        accumulation.samples += frame_samples;
+        let total_samples = accumulation.samples as f64;
+
+        // Quando um bloco fica pronto, soma ele no buffer de acumulação (a imagem mostrada é a média
+        // de todas as amostras até agora) e já atualiza o buffer HDR
+        let sum = &mut accumulation.sum;
+        let mut finish_tile = |tile: Tile, colors: Vec<Vec3>| {
+            // blocos de um frame cancelado voltam vazios
+            if colors.is_empty() { return; }
+            for (pixel, color) in tile.pixels(cols).zip(colors) {
+                sum[pixel] += color * frame_samples as f64;
+                hdr_buffer[pixel] = sum[pixel] / total_samples;
+            }
+            on_tile(tile, hdr_buffer);
+        };
+
+        if refining {
+            render_tiles(cols, rows, frame_seed, cancel, |pixel, rng| {
+                let (row, col) = ((pixel / cols) as f64, (pixel % cols) as f64);
+                let mut ieye = Vec3::NULL;
+                for _ in 0..frame_samples {
+                    let (row, col) = (row + rng.next_f64() - 0.5, col + rng.next_f64() - 0.5);
+                    ieye += if path_tracing {
+                        scene.trace_path(&primary_ray(row, col, rng), max_bounces, rng)
+                    } else {
+                        sample(row, col, rng).0
+                    };
+                }
+                ieye / frame_samples as f64
+            }, &mut finish_tile);
+        } else if adaptive_aa {
+            // Primeira passada: uma amostra no centro de cada pixel
+            let mut first_pass = vec![(Vec3::NULL, None); num_pixels];
+            render_pixels(&mut first_pass, cols, 0, cancel, |pixel, rng| sample((pixel / cols) as f64, (pixel % cols) as f64, rng));
+
+            // Segunda passada: só os pixels com muito contraste com algum vizinho ganham mais amostras
+            let first_pass = &first_pass;
+            render_tiles(cols, rows, 0, cancel, |pixel, rng| {
+                let (row, col) = (pixel / cols, pixel % cols);
+                let (color, shape) = first_pass[pixel];
+                let neighbours = [
+                    (row > 0).then(|| pixel - cols),
+                    (pixel + cols < num_pixels).then_some(pixel + cols),
+                    (col > 0).then(|| pixel - 1),
+                    (col + 1 < cols).then_some(pixel + 1),
+                ];
+                let has_edge = neighbours.into_iter().flatten().any(|n| {
+                    let (n_color, n_shape) = first_pass[n];
+                    n_shape != shape || max_difference(color, n_color) > contrast_threshold
+                });
+
+                if has_edge && max_subdivision > 0 {
+                    subdivide_pixel(&sample, rng, row as f64, col as f64, 1.0, max_subdivision - 1, contrast_threshold)
+                } else {
+                    color
+                }
+            }, &mut finish_tile);
+        } else {
+            // Lança várias amostras dentro de cada pixel e tira a média delas
+            render_tiles(cols, rows, 0, cancel, |pixel, rng| {
+                let (row, col) = ((pixel / cols) as f64, (pixel % cols) as f64);
+                let mut offsets = Vec::with_capacity(samples_per_pixel as usize);
+                sample_pattern.offsets(samples_per_pixel, rng, &mut offsets);
+                let ieye = offsets.iter()
+                    .fold(Vec3::NULL, |acc, &(ox, oy)| acc + sample(row + oy, col + ox, rng).0);
+                ieye / offsets.len() as f64
+            }, &mut finish_tile);
+        }
+    }
+}
+
+// Intervalo mínimo entre duas atualizações da imagem parcial enquanto um frame é renderizado
+pub(super) const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Converte a cor HDR (linear) `color` pra um pixel RGBA de 8 bits, com tone mapping e codificação sRGB
+fn to_rgba8(color: Vec3, tone_mapping: ToneMapping, exposure: f64) -> Rgba8 {
+    // converte pra range de u8, etc.
+    let ieye = tone_mapping.apply(color, exposure).linear_to_srgb() * 255.0;
+    [ieye.x as u8, ieye.y as u8, ieye.z as u8, 255]
+}
+
+// Lado (em pixels) dos blocos em que a imagem é dividida pra renderizar
+const TILE_SIZE: usize = 16;
+
+#[derive(Clone, Copy)]
+/// Bloco retangular de pixels da imagem (linhas `row0..row1`, colunas `col0..col1`)
+pub struct Tile {
+    pub row0: usize, pub row1: usize,
+    pub col0: usize, pub col1: usize,
+}
+
+impl Tile {
+    /// Índices (na imagem inteira, de `cols` colunas) dos pixels do bloco, linha por linha
+    pub fn pixels(&self, cols: usize) -> impl Iterator<Item = usize> {
+        let (col0, col1) = (self.col0, self.col1);
+        (self.row0..self.row1).flat_map(move |row| (col0..col1).map(move |col| row*cols + col))
+    }
+}
+
+/// Divide uma imagem de `cols` x `rows` pixels em blocos de `TILE_SIZE` x `TILE_SIZE`
+fn tiles(cols: usize, rows: usize) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    for row0 in (0..rows).step_by(TILE_SIZE) {
+        for col0 in (0..cols).step_by(TILE_SIZE) {
+            tiles.push(Tile {
+                row0, row1: (row0 + TILE_SIZE).min(rows),
+                col0, col1: (col0 + TILE_SIZE).min(cols),
+            });
+        }
+    }
+    tiles
+}
+
+/// Calcula todos os pixels de uma imagem de `cols` x `rows` em paralelo com
+/// `f(índice do pixel, gerador aleatório)`. \
+/// A imagem é dividida em blocos pequenos que as threads do pool global vão pegando de uma fila,
+/// então a carga fica balanceada mesmo quando algumas regiões são bem mais pesadas que outras. \
+/// `on_tile(bloco, valores)` é chamado na thread atual assim que cada bloco fica pronto
+/// (valores na ordem de `Tile::pixels`, ou vazio se `cancel` virou `true` antes do bloco começar). \
+/// `seed`: seed dos geradores aleatórios (mudar a seed muda as amostras sorteadas)
+fn render_tiles<T: Send>(
+    cols: usize, rows: usize, seed: u64, cancel: &AtomicBool,
+    f: impl Fn(usize, &mut Rng) -> T + Sync,
+    mut on_tile: impl FnMut(Tile, Vec<T>)
+) {
+    let tiles = tiles(cols, rows);
+    let tiles = &tiles;
+    ThreadPool::global().for_each(tiles.len(), |i| {
+        if cancel.load(Ordering::Relaxed) { return Vec::new(); }
+        // Cada bloco tem seu próprio gerador de números aleatórios
+        // (o resultado não depende de qual thread pegou o bloco)
+        let mut rng = Rng::new(seed.wrapping_mul(0x9E37_79B9) ^ i as u64);
+        tiles[i].pixels(cols).map(|pixel| f(pixel, &mut rng)).collect()
+    }, |i, values| on_tile(tiles[i], values));
+}
+
+/// Calcula todos os pixels de `buffer` (uma imagem com `cols` colunas) em paralelo com `render_tiles`
+fn render_pixels<T: Send>(buffer: &mut [T], cols: usize, seed: u64, cancel: &AtomicBool, f: impl Fn(usize, &mut Rng) -> T + Sync) {
+    render_tiles(cols, buffer.len() / cols, seed, cancel, f, |tile, values| {
+        for (pixel, value) in tile.pixels(cols).zip(values) {
+            buffer[pixel] = value;
+        }
+    });
+}
+
+/// Maior diferença entre os canais de duas cores
+fn max_difference(a: Vec3, b: Vec3) -> f64 {
+    let d = a - b;
+    d.x.abs().max(d.y.abs()).max(d.z.abs())
+}
+
+/// Anti-aliasing adaptativo: amostra os 4 quadrantes de um quadrado de lado `size` (em pixels)
+/// centrado em (`row`, `col`) e subdivide os quadrantes enquanto houver contraste entre as amostras
+/// (até mais `subdivisions` vezes). Retorna a média das amostras.
+fn subdivide_pixel(
+    sample: &impl Fn(f64, f64, &mut Rng) -> (Vec3, Option<usize>),
+    rng: &mut Rng,
+    row: f64, col: f64, size: f64,
+    subdivisions: u32, threshold: f64
+) -> Vec3 {
+    let q = size / 4.0;
+    let centers = [(row - q, col - q), (row - q, col + q), (row + q, col - q), (row + q, col + q)];
+    let samples = centers.map(|(r, c)| sample(r, c, rng));
+
+    let has_contrast = samples.iter().any(|(color, shape)| {
+        *shape != samples[0].1 || max_difference(*color, samples[0].0) > threshold
+    });
+    if subdivisions > 0 && has_contrast {
+        centers.into_iter()
+            .fold(Vec3::NULL, |acc, (r, c)| acc + subdivide_pixel(sample, rng, r, c, size/2.0, subdivisions - 1, threshold))
+            / 4.0
+    } else {
+        samples.iter().fold(Vec3::NULL, |acc, (color, _)| acc + *color) / 4.0
+    }
+}
+
+/// Simula uma lente fina de raio `aperture`: sorteia a origem do raio num disco em volta da origem
+/// original (no plano da câmera) e aponta o raio pro mesmo ponto do plano de foco. \
+/// (Pontos no plano de foco ficam nítidos, o resto fica borrado.) \
+/// O plano de foco fica a `focus_distance` da posição `pos` da câmera (igual a `Camera::focus_on`), mesmo
+/// nas projeções em que a origem dos raios fica no viewport.
+fn apply_lens(ray: &mut Ray, aperture: f64, focus_distance: f64, pos: Vec3, coord_system: [Vec3; 3], rng: &mut Rng) {
+    let forward = -coord_system[2];
+    let dr = ray.dr.normalized();
+    // distância que falta da origem do raio até o plano de foco, ao longo do eixo de visada
+    let remaining = focus_distance - (ray.origin - pos).dot(forward);
+    let focus_point = ray.origin + dr * (remaining / dr.dot(forward));
+
+    let (x, y) = rng.unit_disk();
+    ray.origin += aperture * (x*coord_system[0] + y*coord_system[1]);
+    ray.dr = (focus_point - ray.origin).normalized();
+}
+
+/// Ajusta o raio `ray` pra passar pelo ponto (`row`, `col`) do viewport. \
+/// (`row` e `col` podem ser fracionários, o centro do pixel (i, j) é (i.0, j.0))
+fn aim_ray(ray: &mut Ray, projection_type: Projection, viewport: &Viewport, pos: Vec3, row: f64, col: f64) {
+    let p = viewport.p00 + col*viewport.dx - row*viewport.dy;
+    match projection_type {
+        Projection::Perspective => { ray.dr = (p - pos).normalized(); }
+        Projection::Ortographic => { ray.origin = p; }
+        Projection::Oblique => { ray.origin = p; }
+    };
+}
 
 #[derive(Clone, PartialEq)]
 /// Janela através a qual o observador vai olhar \
@@ -0,0 +1,106 @@
+use super::AuxBuffers;
+use crate::utils::{ThreadPool, Vec3};
+
+// Pesos do filtro B3-spline (1/16, 1/4, 3/8, 1/4, 1/16) usado em cada passada do à-trous
+const KERNEL: [f64; 5] = [1.0/16.0, 1.0/4.0, 3.0/8.0, 1.0/4.0, 1.0/16.0];
+
+#[derive(Clone, Copy, PartialEq)]
+/// Filtro de ruído "à-trous" (wavelet) guiado pelos passes auxiliares: cada passada faz uma média 5x5
+/// com os vizinhos cada vez mais espaçados (1, 2, 4, ... pixels), e os vizinhos de outro lado de uma
+/// borda (normal, profundidade ou albedo diferentes) quase não contam. \
+/// A cor é dividida pelo albedo antes do filtro e multiplicada de volta depois, então as texturas não borram. \
+/// `iterations`: número de passadas (o raio do filtro dobra a cada passada) \
+/// `color_sigma`: tolerância a diferenças de cor (diminui pela metade a cada passada) \
+/// `normal_power`: expoente do peso das normais (maior = bordas mais nítidas) \
+/// `depth_sigma`: tolerância a diferenças de profundidade (relativa à distância, por pixel) \
+/// `albedo_sigma`: tolerância a diferenças de albedo
+pub struct Denoiser {
+    pub iterations: u32,
+    pub color_sigma: f64,
+    pub normal_power: f64,
+    pub depth_sigma: f64,
+    pub albedo_sigma: f64,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Denoiser { iterations: 4, color_sigma: 1.0, normal_power: 32.0, depth_sigma: 0.02, albedo_sigma: 0.1 }
+    }
+}
+
+impl Denoiser {
+    #[must_use]
+    /// Retorna a imagem `colors` (HDR, linha por linha) sem ruído, usando os passes auxiliares `aux`
+    /// da mesma imagem como guia. (Os pixels de céu não são filtrados.)
+    pub fn apply(&self, colors: &[Vec3], aux: &AuxBuffers) -> Vec<Vec3> {
+        let (cols, rows) = (aux.cols as usize, aux.rows as usize);
+        if colors.len() != cols * rows || aux.depth.len() != colors.len() { return colors.to_vec(); }
+
+        // separa a iluminação da cor do material
+        let mut illumination: Vec<Vec3> = colors.iter().zip(&aux.albedo)
+            .map(|(c, a)| demodulate(*c, *a))
+            .collect();
+        let mut next = illumination.clone();
+
+        for i in 0..self.iterations.min(12) {
+            let step = 1 << i;
+            let color_sigma = self.color_sigma / (1 << i) as f64;
+            let input = &illumination;
+            ThreadPool::global().for_each(rows, |row| {
+                (0..cols).map(|col| self.filter_pixel(input, aux, cols, rows, row, col, step, color_sigma)).collect::<Vec<_>>()
+            }, |row, values| next[row*cols..(row + 1)*cols].copy_from_slice(&values));
+            std::mem::swap(&mut illumination, &mut next);
+        }
+
+        illumination.iter().zip(&aux.albedo).zip(colors).zip(&aux.shape_index)
+            .map(|(((l, a), c), shape)| if shape.is_some() { remodulate(*l, *a) } else { *c })
+            .collect()
+    }
+
+    // Uma passada do filtro no pixel (row, col), com os vizinhos a `step` pixels de distância
+    #[allow(clippy::too_many_arguments)]
+    fn filter_pixel(
+        &self, input: &[Vec3], aux: &AuxBuffers, cols: usize, rows: usize,
+        row: usize, col: usize, step: usize, color_sigma: f64
+    ) -> Vec3 {
+        let p = row*cols + col;
+        if aux.shape_index[p].is_none() { return input[p]; }
+        let (color, normal, depth, albedo) = (input[p], aux.normal[p], aux.depth[p], aux.albedo[p]);
+
+        let mut sum = Vec3::NULL;
+        let mut weight_sum = 0.0;
+        for (i, ki) in KERNEL.iter().enumerate() {
+            let r = row as isize + (i as isize - 2) * step as isize;
+            if r < 0 || r >= rows as isize { continue; }
+            for (j, kj) in KERNEL.iter().enumerate() {
+                let c = col as isize + (j as isize - 2) * step as isize;
+                if c < 0 || c >= cols as isize { continue; }
+                let q = r as usize * cols + c as usize;
+                if aux.shape_index[q].is_none() { continue; }
+
+                // pesos que "param" o filtro nas bordas
+                let w_normal = normal.dot(aux.normal[q]).max(0.0).powf(self.normal_power);
+                let distance = ((i as f64 - 2.0).powi(2) + (j as f64 - 2.0).powi(2)).sqrt() * step as f64;
+                let w_depth = (-(depth - aux.depth[q]).abs() / (self.depth_sigma * depth * distance).max(1e-6)).exp();
+                let w_albedo = (-(albedo - aux.albedo[q]).length_squared() / (self.albedo_sigma * self.albedo_sigma)).exp();
+                let w_color = (-(color - input[q]).length_squared() / (color_sigma * color_sigma).max(1e-8)).exp();
+
+                let w = ki * kj * w_normal * w_depth * w_albedo * w_color;
+                sum += input[q] * w;
+                weight_sum += w;
+            }
+        }
+        if weight_sum > 0.0 { sum / weight_sum } else { color }
+    }
+}
+
+// Pequeno valor somado ao albedo pra não dividir por zero nos materiais pretos
+const ALBEDO_EPSILON: f64 = 0.01;
+
+fn demodulate(color: Vec3, albedo: Vec3) -> Vec3 {
+    Vec3::new(color.x / (albedo.x + ALBEDO_EPSILON), color.y / (albedo.y + ALBEDO_EPSILON), color.z / (albedo.z + ALBEDO_EPSILON))
+}
+
+fn remodulate(illumination: Vec3, albedo: Vec3) -> Vec3 {
+    illumination * (albedo + Vec3::all(ALBEDO_EPSILON))
+}
@@ -0,0 +1,250 @@
+#![allow(dead_code)]
+use std::f64::consts::PI;
+use std::sync::Arc;
+use super::shapes::Texture;
+use crate::utils::{Rng, Vec3};
+
+// Nomes dos arquivos das faces de um cube map, na ordem +X, -X, +Y, -Y, +Z, -Z
+const CUBE_FACES: [&str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+#[derive(Clone)]
+/// Imagem do ambiente em volta da cena. \
+/// `Equirectangular`: panorama 360° (longitude no eixo x da imagem, latitude no eixo y) \
+/// `CubeMap`: seis faces de um cubo, na ordem +X, -X, +Y, -Y, +Z, -Z (convenção do OpenGL)
+pub enum EnvironmentMap {
+    // As imagens são compartilhadas (`Arc`) porque a cena é copiada pra thread de renderização a cada mudança
+    Equirectangular(Arc<Texture>),
+    CubeMap(Arc<[Texture; 6]>),
+}
+
+// Dois mapas são iguais se compartilham as mesmas imagens
+impl PartialEq for EnvironmentMap {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EnvironmentMap::Equirectangular(a), EnvironmentMap::Equirectangular(b)) => Arc::ptr_eq(a, b),
+            (EnvironmentMap::CubeMap(a), EnvironmentMap::CubeMap(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+/// Ambiente (céu) da cena: a cor que chega pelos raios que não atingem nenhum objeto. \
+/// `rotation`: rotação do ambiente em volta do eixo Y (radianos) \
+/// `intensity`: multiplicador da cor da imagem \
+/// `lighting`: se o ambiente também ilumina a cena (image-based lighting, substitui a luz ambiente) \
+/// `samples`: número de direções do ambiente amostradas por ponto iluminado (no modo Whitted)
+pub struct Environment {
+    pub map: EnvironmentMap,
+    pub rotation: f64,
+    pub intensity: f64,
+    pub lighting: bool,
+    pub samples: u32,
+    sampler: Option<Arc<EnvironmentSampler>>, // só pros panoramas
+}
+
+// (o `sampler` é calculado a partir do `map`, então não precisa ser comparado)
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map && self.rotation == other.rotation && self.intensity == other.intensity
+            && self.lighting == other.lighting && self.samples == other.samples
+    }
+}
+
+impl Environment {
+    #[inline]
+    #[must_use]
+    /// Cria um ambiente a partir de um panorama equiretangular
+    pub fn equirectangular(texture: Texture) -> Environment {
+        let sampler = EnvironmentSampler::new(&texture).map(Arc::new);
+        Environment {
+            map: EnvironmentMap::Equirectangular(Arc::new(texture)),
+            rotation: 0.0, intensity: 1.0, lighting: true, samples: 4, sampler,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cria um ambiente a partir das seis faces de um cube map (+X, -X, +Y, -Y, +Z, -Z)
+    pub fn cube_map(faces: [Texture; 6]) -> Environment {
+        Environment {
+            map: EnvironmentMap::CubeMap(Arc::new(faces)),
+            rotation: 0.0, intensity: 1.0, lighting: true, samples: 4, sampler: None,
+        }
+    }
+
+    /// Carrega um panorama equiretangular de um arquivo PNG, ou HDR (`.hdr` ou `.pfm`)
+    pub fn load_equirectangular(file_name: &str) -> Result<Environment, String> {
+        let is_hdr = [".hdr", ".pfm"].iter().any(|ext| file_name.to_lowercase().ends_with(ext));
+        let texture = if is_hdr { Texture::new_hdr(file_name)? } else { Texture::try_new(file_name)? };
+        Ok(Environment::equirectangular(texture))
+    }
+
+    /// Carrega um cube map da pasta `dir`, com as faces nos arquivos `px.png`, `nx.png`, `py.png`,
+    /// `ny.png`, `pz.png` e `nz.png`
+    pub fn load_cube_map(dir: &str) -> Result<Environment, String> {
+        let mut faces = Vec::with_capacity(6);
+        for face in CUBE_FACES {
+            faces.push(Texture::try_new(&format!("{dir}/{face}.png"))?);
+        }
+        let faces: [Texture; 6] = faces.try_into().unwrap_or_else(|_| unreachable!());
+        Ok(Environment::cube_map(faces))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna o ambiente girado `rotation` radianos em volta do eixo Y
+    pub fn with_rotation(self, rotation: f64) -> Environment {
+        Environment { rotation, ..self }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna o ambiente com a cor multiplicada por `intensity`
+    pub fn with_intensity(self, intensity: f64) -> Environment {
+        Environment { intensity, ..self }
+    }
+
+    #[must_use]
+    /// Cor (linear) do ambiente vista na direção `dr`
+    pub fn sample(&self, dr: Vec3) -> Vec3 {
+        // gira a direção ao contrário, em vez de girar a imagem
+        let d = rotate_y(dr, -self.rotation).normalized();
+
+        let color = match &self.map {
+            EnvironmentMap::Equirectangular(texture) => {
+                let (u, v) = direction_to_uv(d);
+                texture.sample(u, v)
+            }
+            EnvironmentMap::CubeMap(faces) => {
+                // escolhe a face pelo maior componente da direção, e projeta a direção nela
+                let (ax, ay, az) = (d.x.abs(), d.y.abs(), d.z.abs());
+                let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+                    if d.x > 0.0 { (0, -d.z, -d.y, ax) } else { (1, d.z, -d.y, ax) }
+                } else if ay >= az {
+                    if d.y > 0.0 { (2, d.x, d.z, ay) } else { (3, d.x, -d.z, ay) }
+                } else if d.z > 0.0 {
+                    (4, d.x, -d.y, az)
+                } else {
+                    (5, -d.x, -d.y, az)
+                };
+                faces[face].sample((sc/ma + 1.0) / 2.0, (tc/ma + 1.0) / 2.0)
+            }
+        };
+        color * self.intensity
+    }
+
+    /// Sorteia uma direção de onde vem luz do ambiente, com mais chance nas partes mais brilhantes
+    /// do panorama (importance sampling; os cube maps são amostrados uniformemente). \
+    /// Retorna a direção (unitária), a cor que vem dela, e a densidade de probabilidade (por ângulo sólido).
+    pub fn sample_light(&self, rng: &mut Rng) -> (Vec3, Vec3, f64) {
+        let dr = match &self.sampler {
+            Some(sampler) => {
+                let (u, v) = sampler.sample(rng);
+                rotate_y(uv_to_direction(u, v), self.rotation)
+            }
+            None => {
+                let y = 1.0 - 2.0*rng.next_f64();
+                let phi = 2.0*PI*rng.next_f64();
+                let r = (1.0 - y*y).max(0.0).sqrt();
+                Vec3::new(r*phi.cos(), y, r*phi.sin())
+            }
+        };
+        (dr, self.sample(dr), self.light_pdf(dr))
+    }
+
+    #[must_use]
+    /// Densidade de probabilidade (por ângulo sólido) de `sample_light` sortear a direção `dr`
+    pub fn light_pdf(&self, dr: Vec3) -> f64 {
+        match &self.sampler {
+            None => 1.0 / (4.0*PI),
+            Some(sampler) => {
+                let d = rotate_y(dr, -self.rotation).normalized();
+                let (u, v) = direction_to_uv(d);
+                let sin_theta = (1.0 - d.y*d.y).max(0.0).sqrt();
+                if sin_theta <= 0.0 { return 0.0; }
+                // o panorama estica os pixels perto dos polos, então a densidade por ângulo sólido é menor lá
+                sampler.pdf(u, v) / (2.0*PI*PI*sin_theta)
+            }
+        }
+    }
+}
+
+// Distribuição dos pixels de um panorama, proporcional ao brilho de cada pixel vezes a área que ele cobre
+// na esfera. Sorteia uma linha pela distribuição das linhas, e depois um pixel pela distribuição da linha.
+struct EnvironmentSampler {
+    width: usize,
+    height: usize,
+    rows_cdf: Vec<f64>, // distribuição acumulada das linhas (height + 1 valores)
+    cols_cdf: Vec<f64>, // distribuição acumulada de cada linha (width + 1 valores por linha)
+    pdf: Vec<f64>, // densidade de probabilidade de cada pixel, nas coordenadas (u, v)
+}
+
+impl EnvironmentSampler {
+    // Retorna `None` se o panorama for todo preto
+    fn new(texture: &Texture) -> Option<EnvironmentSampler> {
+        let (width, height) = (texture.width as usize, texture.height as usize);
+        let mut weights = Vec::with_capacity(width * height);
+        let mut rows_cdf = Vec::with_capacity(height + 1);
+        let mut cols_cdf = Vec::with_capacity(height * (width + 1));
+        rows_cdf.push(0.0);
+        for y in 0..height {
+            let sin_theta = (PI * (y as f64 + 0.5) / height as f64).sin();
+            let mut row_sum = 0.0;
+            cols_cdf.push(0.0);
+            for x in 0..width {
+                let color = texture.sample((x as f64 + 0.5) / width as f64, (y as f64 + 0.5) / height as f64);
+                let weight = (0.2126*color.x + 0.7152*color.y + 0.0722*color.z).max(0.0) * sin_theta;
+                weights.push(weight);
+                row_sum += weight;
+                cols_cdf.push(row_sum);
+            }
+            rows_cdf.push(rows_cdf[y] + row_sum);
+        }
+
+        let total = rows_cdf[height];
+        if total <= 0.0 { return None; }
+        let pdf = weights.iter().map(|w| w / total * (width * height) as f64).collect();
+        Some(EnvironmentSampler { width, height, rows_cdf, cols_cdf, pdf })
+    }
+
+    // Sorteia as coordenadas (u, v) de um ponto do panorama
+    fn sample(&self, rng: &mut Rng) -> (f64, f64) {
+        let y = sample_cdf(&self.rows_cdf, rng.next_f64());
+        let x = sample_cdf(&self.cols_cdf[y*(self.width + 1)..(y + 1)*(self.width + 1)], rng.next_f64());
+        ((x as f64 + rng.next_f64()) / self.width as f64, (y as f64 + rng.next_f64()) / self.height as f64)
+    }
+
+    fn pdf(&self, u: f64, v: f64) -> f64 {
+        let x = ((u * self.width as f64) as usize).min(self.width - 1);
+        let y = ((v * self.height as f64) as usize).min(self.height - 1);
+        self.pdf[y*self.width + x]
+    }
+}
+
+// Sorteia um índice `i` da distribuição acumulada `cdf` (com `cdf[i] <= r*total < cdf[i+1]`)
+fn sample_cdf(cdf: &[f64], r: f64) -> usize {
+    let target = r * cdf[cdf.len() - 1];
+    (cdf.partition_point(|&c| c <= target) - 1).min(cdf.len() - 2)
+}
+
+// Gira a direção `dr` em `angle` radianos em volta do eixo Y
+fn rotate_y(dr: Vec3, angle: f64) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    Vec3::new(cos*dr.x + sin*dr.z, dr.y, -sin*dr.x + cos*dr.z)
+}
+
+// Coordenadas (u, v) do panorama equiretangular na direção unitária `d`
+// (-Z fica no meio da imagem, e o topo da imagem é o +Y)
+fn direction_to_uv(d: Vec3) -> (f64, f64) {
+    let u = 0.5 + d.x.atan2(-d.z) / (2.0*PI);
+    let v = d.y.clamp(-1.0, 1.0).acos() / PI;
+    (u.rem_euclid(1.0), v)
+}
+
+// Inverso de `direction_to_uv`
+fn uv_to_direction(u: f64, v: f64) -> Vec3 {
+    let (sin_theta, cos_theta) = (v * PI).sin_cos();
+    let (sin_phi, cos_phi) = ((u - 0.5) * 2.0*PI).sin_cos();
+    Vec3::new(sin_theta*sin_phi, cos_theta, -sin_theta*cos_phi)
+}
@@ -0,0 +1,96 @@
+use crate::utils::Vec3;
+
+// Distância máxima percorrida pelos raios que não atingem nada no cálculo dos feixes de luz volumétricos
+const MAX_VOLUMETRIC_DISTANCE: f64 = 50.0;
+
+#[derive(Clone, Copy, PartialEq)]
+/// Névoa (meio participante) da cena. A densidade num ponto de altura `y` é
+/// `density + height_density * e^(-height_falloff * (y - height))`: \
+/// `density`: névoa exponencial uniforme (a luz cai pela metade a cada `ln(2)/density` metros) \
+/// `height_density`, `height`, `height_falloff`: névoa de altura, mais densa perto do chão
+/// (`height_density` na altura `height`, diminuindo exponencialmente pra cima) \
+/// `color`: cor (linear) da luz espalhada pela névoa (normalmente a cor do céu) \
+/// `volumetric_samples`: número de amostras ao longo de cada raio pra calcular os feixes de luz das
+/// spotlights espalhados pela névoa (0 = sem feixes volumétricos)
+pub struct Fog {
+    pub color: Vec3,
+    pub density: f64,
+    pub height_density: f64,
+    pub height: f64,
+    pub height_falloff: f64,
+    pub volumetric_samples: u32,
+}
+
+impl Fog {
+    #[inline]
+    #[must_use]
+    /// Cria uma névoa exponencial uniforme de cor `color` e densidade `density`
+    pub fn exponential(color: Vec3, density: f64) -> Fog {
+        Fog { color, density, height_density: 0.0, height: 0.0, height_falloff: 1.0, volumetric_samples: 0 }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cria uma névoa de altura de cor `color`, com densidade `density` na altura `height`
+    /// que diminui com fator `falloff` pra cima
+    pub fn height(color: Vec3, density: f64, height: f64, falloff: f64) -> Fog {
+        Fog { color, density: 0.0, height_density: density, height, height_falloff: falloff, volumetric_samples: 0 }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna a névoa com feixes de luz volumétricos calculados com `samples` amostras por raio
+    pub fn with_volumetric_samples(self, samples: u32) -> Fog {
+        Fog { volumetric_samples: samples, ..self }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Densidade da névoa no ponto `p`
+    pub fn density_at(&self, p: Vec3) -> f64 {
+        self.density + self.height_density * (-self.height_falloff * (p.y - self.height)).exp()
+    }
+
+    #[must_use]
+    /// Fração da luz que atravessa a névoa do ponto `origin` até `origin + distance*dr`
+    /// (`dr` unitário, `distance` pode ser infinita)
+    pub fn transmittance(&self, origin: Vec3, dr: Vec3, distance: f64) -> Vec3 {
+        Vec3::all((-self.optical_depth(origin, dr, distance)).exp())
+    }
+
+    /// Integral da densidade ao longo do raio (calculada analiticamente)
+    fn optical_depth(&self, origin: Vec3, dr: Vec3, distance: f64) -> f64 {
+        let mut depth = 0.0;
+        if self.density > 0.0 { depth += self.density * distance; }
+        if self.height_density > 0.0 {
+            let start = self.height_density * (-self.height_falloff * (origin.y - self.height)).exp();
+            let k = self.height_falloff * dr.y;
+            depth += if k.abs() < 1e-9 {
+                start * distance // raio horizontal: densidade constante
+            } else if distance.is_infinite() {
+                if k > 0.0 { start / k } else { f64::INFINITY }
+            } else {
+                start * (1.0 - (-k * distance).exp()) / k
+            };
+        }
+        depth
+    }
+
+    #[must_use]
+    /// Fração da luz que chega no ponto `p` vinda de uma luz na direção `ldr` (ver `Light::sample`). \
+    /// A névoa uniforme não tem fim, então a luz de uma luz direcional só é atenuada pela névoa de altura.
+    pub fn light_transmittance(&self, p: Vec3, ldr: Vec3, is_directional: bool) -> Vec3 {
+        if is_directional {
+            Fog { density: 0.0, ..*self }.transmittance(p, ldr.normalized(), f64::INFINITY)
+        } else {
+            self.transmittance(p, ldr.normalized(), ldr.length())
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Distância ao longo de um raio que é amostrada pros feixes volumétricos (`None` = o raio não atingiu nada)
+    pub fn volumetric_distance(t: Option<f64>) -> f64 {
+        t.unwrap_or(MAX_VOLUMETRIC_DISTANCE).min(MAX_VOLUMETRIC_DISTANCE)
+    }
+}
@@ -1,13 +1,49 @@
 #![allow(dead_code)]
-use crate::utils::Vec3;
+use std::f64::consts::PI;
+use crate::utils::{Rng, Vec3};
+
+#[derive(Clone, Copy, PartialEq)]
+/// Atenuação da luz com a distância `d`: a intensidade é dividida por `constant + linear*d + quadratic*d²`. \
+/// (`NONE` = a luz não enfraquece com a distância, `INVERSE_SQUARE` = fisicamente correto)
+pub struct Attenuation {
+    pub constant: f64,
+    pub linear: f64,
+    pub quadratic: f64,
+}
+
+impl Attenuation {
+    pub const NONE: Attenuation = Attenuation { constant: 1.0, linear: 0.0, quadratic: 0.0 };
+    pub const INVERSE_SQUARE: Attenuation = Attenuation { constant: 0.0, linear: 0.0, quadratic: 1.0 };
+
+    #[inline]
+    #[must_use]
+    pub fn new(constant: f64, linear: f64, quadratic: f64) -> Attenuation {
+        Attenuation { constant, linear, quadratic }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Fator que multiplica a intensidade da luz a uma distância `d`
+    pub fn factor(&self, d: f64) -> f64 {
+        let denominator = self.constant + self.linear*d + self.quadratic*d*d;
+        if denominator > 0.0 { 1.0 / denominator } else { 1.0 }
+    }
+}
 
 #[derive(Clone, PartialEq)]
 /// Luz de posição `pos`, cor `color`, e intensidade `intensity`. \
-/// (durante a renderização, a cor é o mesmo que `color * intensity`)
+/// (durante a renderização, a cor é o mesmo que `color * intensity`) \
+/// As luzes de área (`Rectangle`, `Disk`, `Sphere`) são amostradas em `samples` pontos,
+/// o que gera sombras suaves (com penumbra). Nelas, `intensity` é a radiância emitida pela superfície,
+/// então a luz que chega num ponto depende do tamanho da luz vista dele (área, cosseno e distância²). \
+/// A spotlight ilumina com intensidade total até `inner_angle`, e vai apagando suavemente até `angle`.
 pub enum Light {
-    Point { pos: Vec3, intensity: Vec3 }, // Posição da luz no cenário
-    Spotlight { pos: Vec3, dr: Vec3, angle: f64, intensity: Vec3 },
+    Point { pos: Vec3, intensity: Vec3, attenuation: Attenuation }, // Posição da luz no cenário
+    Spotlight { pos: Vec3, dr: Vec3, angle: f64, inner_angle: f64, intensity: Vec3, attenuation: Attenuation },
     Directional { dr: Vec3, intensity: Vec3 },
+    Rectangle { pos: Vec3, u: Vec3, v: Vec3, intensity: Vec3, samples: u32 }, // retângulo de centro `pos` e lados `u` e `v`
+    Disk { pos: Vec3, dr: Vec3, radius: f64, intensity: Vec3, samples: u32 }, // disco que ilumina na direção `-dr`
+    Sphere { pos: Vec3, radius: f64, intensity: Vec3, samples: u32 },
 }
 
 impl Light {
@@ -15,14 +51,14 @@ impl Light {
     #[must_use]
     /// Cria uma point light com posição `pos`, e intensidade `intensity * color`.
     pub fn point(pos: Vec3, color: Vec3, intensity: f64) -> Light {
-        Light::Point { pos, intensity: color*intensity }
+        Light::Point { pos, intensity: color*intensity, attenuation: Attenuation::NONE }
     }
 
     #[inline]
     #[must_use]
     /// Cria uma spotlight com posição `pos`, direção `dr`, ângulo `angle`, e intensidade `intensity * color`.
     pub fn spotlight(pos: Vec3, dr: Vec3, angle: f64, color: Vec3, intensity: f64) -> Light {
-        Light::Spotlight { pos, dr:-dr, angle, intensity: color*intensity }
+        Light::Spotlight { pos, dr:-dr, angle, inner_angle: angle, intensity: color*intensity, attenuation: Attenuation::NONE }
     }
 
     #[inline]
@@ -31,4 +67,143 @@ impl Light {
     pub fn directional(dr: Vec3, color: Vec3, intensity: f64) -> Light {
         Light::Directional { dr:-dr, intensity: color*intensity }
     }
+
+    #[inline]
+    #[must_use]
+    /// Cria uma luz retangular de centro `pos` e lados `u` e `v`, com intensidade `intensity * color`,
+    /// amostrada em `samples` pontos. (ilumina dos dois lados do retângulo)
+    pub fn rectangle(pos: Vec3, u: Vec3, v: Vec3, color: Vec3, intensity: f64, samples: u32) -> Light {
+        Light::Rectangle { pos, u, v, intensity: color*intensity, samples }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cria uma luz em forma de disco de centro `pos`, virada pra direção `dr`, de raio `radius`,
+    /// com intensidade `intensity * color`, amostrada em `samples` pontos.
+    pub fn disk(pos: Vec3, dr: Vec3, radius: f64, color: Vec3, intensity: f64, samples: u32) -> Light {
+        Light::Disk { pos, dr: -dr.normalized(), radius, intensity: color*intensity, samples }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cria uma luz esférica de centro `pos` e raio `radius`, com intensidade `intensity * color`,
+    /// amostrada em `samples` pontos.
+    pub fn sphere(pos: Vec3, radius: f64, color: Vec3, intensity: f64, samples: u32) -> Light {
+        Light::Sphere { pos, radius, intensity: color*intensity, samples }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna a luz com a intensidade multiplicada por `factor`
+    pub fn scaled(mut self, factor: f64) -> Light {
+        match &mut self {
+            Light::Point { intensity, .. } | Light::Spotlight { intensity, .. } | Light::Directional { intensity, .. }
+            | Light::Rectangle { intensity, .. } | Light::Disk { intensity, .. } | Light::Sphere { intensity, .. } => {
+                *intensity = *intensity * factor;
+            }
+        }
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna a luz com atenuação `attenuation` (só muda point lights e spotlights)
+    pub fn with_attenuation(mut self, new_attenuation: Attenuation) -> Light {
+        if let Light::Point { attenuation, .. } | Light::Spotlight { attenuation, .. } = &mut self {
+            *attenuation = new_attenuation;
+        }
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna a spotlight com ângulo interno `angle` (graus), a partir do qual ela começa a apagar
+    pub fn with_inner_angle(mut self, angle: f64) -> Light {
+        if let Light::Spotlight { inner_angle, angle: outer_angle, .. } = &mut self {
+            *inner_angle = angle.min(*outer_angle);
+        }
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Número de amostras usadas pra calcular a iluminação dessa luz em um ponto
+    pub fn samples(&self) -> u32 {
+        match self {
+            Light::Rectangle { samples, .. }
+            | Light::Disk { samples, .. }
+            | Light::Sphere { samples, .. } => (*samples).max(1),
+            _ => 1,
+        }
+    }
+
+    #[must_use]
+    /// Amostra a luz a partir do ponto `p`. \
+    /// Retorna o vetor de `p` até o ponto amostrado da luz (ou a direção da luz, se ela for direcional),
+    /// a intensidade que chega desse ponto (já dividida pelo número de amostras e, nas luzes de área,
+    /// dividida pela densidade da amostra), e se a luz é direcional. \
+    /// Retorna `None` se a luz não ilumina `p` (fora do cone da spotlight, atrás do disco, dentro da esfera, etc.)
+    pub fn sample(&self, p: Vec3, rng: &mut Rng) -> Option<(Vec3, Vec3, bool)> {
+        let intensity_per_sample = |intensity: Vec3| intensity / self.samples() as f64;
+        match self {
+            Light::Point { pos, intensity, attenuation } => {
+                let ldr = *pos - p;
+                Some((ldr, *intensity * attenuation.factor(ldr.length()), false))
+            }
+            Light::Spotlight { pos, dr, angle, inner_angle, intensity, attenuation } => {
+                let ldr = *pos - p;
+                let cos = dr.dot(ldr.normalized());
+                let (cos_outer, cos_inner) = (angle.to_radians().cos(), inner_angle.min(*angle).to_radians().cos());
+                if cos <= cos_outer { return None; }
+                // transição suave (smoothstep) entre o cone externo e o interno
+                let falloff = if cos >= cos_inner { 1.0 } else {
+                    let x = (cos - cos_outer) / (cos_inner - cos_outer);
+                    x * x * (3.0 - 2.0*x)
+                };
+                Some((ldr, *intensity * falloff * attenuation.factor(ldr.length()), false))
+            }
+            Light::Directional { dr, intensity } => {
+                Some((*dr, *intensity, true))
+            }
+            Light::Rectangle { pos, u, v, intensity, .. } => {
+                let point = *pos + (rng.next_f64() - 0.5) * *u + (rng.next_f64() - 0.5) * *v;
+                let ldr = point - p;
+                let dist_squared = ldr.length_squared();
+                if dist_squared <= 0.0 { return None; }
+                // |u x v| = área do retângulo, então `area_cos` = área * cosseno na luz (ilumina dos dois lados)
+                let area_cos = u.cross(*v).dot(ldr).abs() / dist_squared.sqrt();
+                Some((ldr, intensity_per_sample(*intensity) * area_cos / dist_squared, false))
+            }
+            Light::Disk { pos, dr, radius, intensity, .. } => {
+                let (bu, bv) = dr.orthonormal_basis();
+                let (x, y) = rng.unit_disk();
+                let ldr = *pos + *radius * (x*bu + y*bv) - p;
+                // o disco só ilumina o lado pra onde ele está virado
+                let dist_squared = ldr.length_squared();
+                if dr.dot(ldr) <= 0.0 || dist_squared <= 0.0 { return None; }
+                let cos_light = dr.dot(ldr) / dist_squared.sqrt();
+                let area = PI * radius * radius;
+                Some((ldr, intensity_per_sample(*intensity) * area * cos_light / dist_squared, false))
+            }
+            Light::Sphere { pos, radius, intensity, .. } => {
+                // `p` dentro da esfera (ou no centro dela) não é iluminado
+                let to_center = *pos - p;
+                let center_dist_squared = to_center.length_squared();
+                if center_dist_squared <= radius * radius { return None; }
+                // sorteia uma direção uniforme no cone que a esfera ocupa vista de `p` (ângulo sólido
+                // 2π(1 - cos_max)), e vai até o ponto da superfície visto nessa direção
+                let center_dist = center_dist_squared.sqrt();
+                let axis = to_center / center_dist;
+                let cos_max = (1.0 - radius * radius / center_dist_squared).max(0.0).sqrt();
+                let cos_theta = 1.0 - rng.next_f64() * (1.0 - cos_max);
+                let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+                let phi = 2.0 * PI * rng.next_f64();
+                let (bu, bv) = axis.orthonormal_basis();
+                let dr = cos_theta * axis + sin_theta * (phi.cos() * bu + phi.sin() * bv);
+                let t = center_dist * cos_theta - (radius * radius - center_dist_squared * sin_theta * sin_theta).max(0.0).sqrt();
+                let solid_angle = 2.0 * PI * (1.0 - cos_max);
+                Some((t * dr, intensity_per_sample(*intensity) * solid_angle, false))
+            }
+        }
+    }
 }
\ No newline at end of file
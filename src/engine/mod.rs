@@ -1,8 +1,23 @@
 pub mod camera;
+pub use camera::Camera;
 mod ray;
 pub use ray::Ray;
 mod light;
-pub use light::Light;
+pub use light::{Attenuation, Light};
+mod fog;
+pub use fog::Fog;
+mod environment;
+pub use environment::{Environment, EnvironmentMap};
+mod sky;
+pub use sky::Sky;
 mod scene;
-pub use scene::Scene;
-pub mod shapes;
\ No newline at end of file
+pub use scene::{AmbientOcclusion, Scene};
+pub mod shapes;
+mod renderer;
+pub use renderer::BackgroundRenderer;
+mod tone_mapping;
+pub use tone_mapping::ToneMapping;
+mod render_pass;
+pub use render_pass::{AuxBuffers, RenderPass};
+mod denoiser;
+pub use denoiser::Denoiser;
\ No newline at end of file
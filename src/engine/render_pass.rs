@@ -0,0 +1,102 @@
+use crate::utils::{save_hdr_as_pfm, ImageF32, Vec3};
+
+#[derive(Clone, Copy, PartialEq)]
+/// Passe da renderização mostrado na janela / exportado \
+/// `Beauty`: a imagem final \
+/// `Depth`: distância da câmera até o objeto atingido (t) \
+/// `Normal`: normal (no espaço do mundo) no ponto atingido \
+/// `Albedo`: cor difusa do material no ponto atingido (com textura) \
+/// `ShapeIndex`: índice do objeto atingido (cada objeto com uma cor)
+pub enum RenderPass {
+    Beauty,
+    Depth,
+    Normal,
+    Albedo,
+    ShapeIndex,
+}
+
+impl RenderPass {
+    pub const ALL: [RenderPass; 5] = [RenderPass::Beauty, RenderPass::Depth, RenderPass::Normal, RenderPass::Albedo, RenderPass::ShapeIndex];
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderPass::Beauty => "Beauty",
+            RenderPass::Depth => "Depth",
+            RenderPass::Normal => "Normal",
+            RenderPass::Albedo => "Albedo",
+            RenderPass::ShapeIndex => "Shape index",
+        }
+    }
+
+    #[must_use]
+    /// Sufixo do nome do arquivo exportado
+    pub fn file_suffix(&self) -> &'static str {
+        match self {
+            RenderPass::Beauty => "beauty",
+            RenderPass::Depth => "depth",
+            RenderPass::Normal => "normal",
+            RenderPass::Albedo => "albedo",
+            RenderPass::ShapeIndex => "index",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+/// Passes auxiliares de uma imagem de `cols` x `rows` pixels (linha por linha), calculados com o raio
+/// que passa pelo centro de cada pixel. Nos pixels em que o raio não atinge nada, a distância é infinita,
+/// a normal é nula, o albedo é a cor do céu e o índice é `None`.
+pub struct AuxBuffers {
+    pub cols: u32,
+    pub rows: u32,
+    pub depth: Vec<f64>,
+    pub normal: Vec<Vec3>, // virada pro lado de onde o raio veio
+    pub albedo: Vec<Vec3>, // linear
+    pub shape_index: Vec<Option<usize>>,
+}
+
+impl AuxBuffers {
+    #[must_use]
+    /// Valores do passe `pass` como cores (a distância e o índice ficam nos três canais, -1 = nenhum objeto). \
+    /// (`Beauty` não é um passe auxiliar, e retorna uma imagem preta)
+    pub fn pass_data(&self, pass: RenderPass) -> Vec<Vec3> {
+        match pass {
+            RenderPass::Beauty => vec![Vec3::NULL; self.depth.len()],
+            RenderPass::Depth => self.depth.iter().map(|&d| Vec3::all(d)).collect(),
+            RenderPass::Normal => self.normal.clone(),
+            RenderPass::Albedo => self.albedo.clone(),
+            RenderPass::ShapeIndex => self.shape_index.iter().map(|i| Vec3::all(i.map_or(-1.0, |i| i as f64))).collect(),
+        }
+    }
+
+    #[must_use]
+    /// Cores (lineares, entre 0 e 1) pra visualizar o passe `pass` na tela: \
+    /// distância: mais perto = mais claro (normalizada pela maior distância da imagem) \
+    /// normal: componentes mapeados de [-1, 1] pra [0, 1] \
+    /// índice: uma cor pseudo-aleatória por objeto
+    pub fn visualize(&self, pass: RenderPass) -> Vec<Vec3> {
+        match pass {
+            RenderPass::Depth => {
+                let max_depth = self.depth.iter().copied().filter(|d| d.is_finite()).fold(0.0, f64::max);
+                self.depth.iter().map(|&d| {
+                    if d.is_finite() && max_depth > 0.0 { Vec3::all(1.0 - d / max_depth).srgb_to_linear() } else { Vec3::NULL }
+                }).collect()
+            }
+            RenderPass::Normal => self.normal.iter().map(|n| ((*n + Vec3::all(1.0)) * 0.5).srgb_to_linear()).collect(),
+            RenderPass::ShapeIndex => self.shape_index.iter().map(|i| i.map_or(Vec3::NULL, index_color)).collect(),
+            RenderPass::Albedo | RenderPass::Beauty => self.pass_data(pass),
+        }
+    }
+
+    /// Salva o passe `pass` (os valores, não a visualização) como uma imagem .pfm
+    pub fn save_pass(&self, pass: RenderPass, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        save_hdr_as_pfm(&ImageF32::from_colors(self.cols, self.rows, &self.pass_data(pass)), file_name)
+    }
+}
+
+// Cor (linear) bem diferente pra cada índice de objeto
+fn index_color(i: usize) -> Vec3 {
+    let hash = (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let channel = |shift: u32| 0.2 + 0.8 * ((hash >> shift) & 0xFF) as f64 / 255.0;
+    Vec3::new(channel(16), channel(32), channel(48))
+}
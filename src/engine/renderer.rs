@@ -0,0 +1,148 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use super::camera::{Accumulator, Camera, CameraSnapshot, PROGRESS_INTERVAL};
+use super::{AuxBuffers, Scene};
+use crate::utils::Vec3;
+
+// Último frame publicado pela thread de renderização (o "front buffer")
+struct Frame {
+    hdr_buffer: Vec<Vec3>,
+    aux_buffers: Arc<AuxBuffers>,
+    resolution: (u32, u32),
+    frames: u32, // número de frames acumulados na imagem
+    is_new: bool, // se ainda não foi mostrado
+}
+
+/// Renderiza a cena numa thread separada, pra interface não travar enquanto um frame pesado é calculado. \
+/// A thread tem sua própria cópia da cena e da câmera (mandadas com `submit`), e fica refinando a imagem
+/// enquanto nada muda. Cada frame pronto é copiado pra um buffer compartilhado, que a thread principal
+/// pega com `present` (double buffering).
+pub struct BackgroundRenderer {
+    jobs: mpsc::Sender<(Arc<Scene>, CameraSnapshot)>,
+    cancel: Arc<AtomicBool>, // avisa a thread que o frame atual não vale mais
+    front: Arc<Mutex<Frame>>,
+    last_job: Option<(Arc<Scene>, CameraSnapshot)>, // último trabalho mandado pra thread (a mesma cópia da cena)
+}
+
+impl BackgroundRenderer {
+    #[must_use]
+    /// Cria a thread de renderização (que fica parada até receber uma cena com `submit`)
+    pub fn new() -> BackgroundRenderer {
+        let (jobs, jobs_rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let front = Arc::new(Mutex::new(Frame { hdr_buffer: Vec::new(), aux_buffers: Arc::default(), resolution: (0, 0), frames: 0, is_new: false }));
+
+        let (thread_cancel, thread_front) = (Arc::clone(&cancel), Arc::clone(&front));
+        thread::Builder::new()
+            .name("render".to_string())
+            .spawn(move || render_loop(&jobs_rx, &thread_cancel, &thread_front))
+            .unwrap();
+
+        BackgroundRenderer { jobs, cancel, front, last_job: None }
+    }
+
+    /// Manda uma cópia da cena e da câmera pra thread de renderização, cancelando o frame que ela estiver
+    /// calculando (tem que ser chamado sempre que a câmera ou a cena podem ter mudado). \
+    /// Se nada mudou desde o último `submit`, não faz nada (a thread continua refinando a imagem).
+    pub fn submit(&mut self, scene: &Scene, camera: &Camera) {
+        let snapshot = camera.snapshot();
+        if self.last_job.as_ref().is_some_and(|(s, c)| **s == *scene && *c == snapshot) { return; }
+        // a cena só é copiada uma vez, e a cópia é dividida com a thread
+        let scene = Arc::new(scene.clone());
+        // cancela antes de mandar: a thread só zera o `cancel` antes de olhar se chegou trabalho novo
+        self.cancel.store(true, Ordering::SeqCst);
+        self.jobs.send((Arc::clone(&scene), snapshot.clone())).unwrap();
+        self.last_job = Some((scene, snapshot));
+    }
+
+    /// Se a thread de renderização publicou um frame novo (da mesma resolução da câmera),
+    /// mostra ele na câmera. Retorna se a imagem da câmera mudou.
+    pub fn present(&self, camera: &mut Camera) -> bool {
+        let mut front = self.front.lock().unwrap();
+        if !front.is_new || front.resolution != (camera.viewport.cols, camera.viewport.rows) { return false; }
+        front.is_new = false;
+        let (frames, aux_buffers) = (front.frames, Arc::clone(&front.aux_buffers));
+        camera.show_frame(&mut front.hdr_buffer, aux_buffers, frames);
+        true
+    }
+}
+
+impl Default for BackgroundRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Loop da thread de renderização: renderiza o trabalho mais recente até a imagem convergir,
+// e espera um trabalho novo quando não tem mais nada pra fazer
+fn render_loop(jobs: &mpsc::Receiver<(Arc<Scene>, CameraSnapshot)>, cancel: &AtomicBool, front: &Mutex<Frame>) {
+    let mut job: Option<(Arc<Scene>, CameraSnapshot)> = None;
+    let mut accumulation = Accumulator::new(0);
+    let mut hdr_buffer = Vec::new(); // "back buffer"
+    let mut aux_buffers = Arc::new(AuxBuffers::default());
+    let mut aux_valid = false; // se `aux_buffers` foi calculado por inteiro pro trabalho atual
+
+    loop {
+        // zera o `cancel` antes de pegar os trabalhos novos: um `submit` que acontecer depois disso
+        // deixa ele `true`, e o frame é cancelado logo em seguida (nunca fica um frame velho publicado)
+        cancel.store(false, Ordering::SeqCst);
+        let idle = job.as_ref().is_none_or(|(_, camera)| camera.is_converged(&accumulation));
+        let mut next_job = if idle {
+            match jobs.recv() {
+                Ok(new_job) => Some(new_job),
+                Err(_) => return, // o `BackgroundRenderer` foi destruído
+            }
+        } else {
+            None
+        };
+        // só o trabalho mais recente interessa
+        while let Ok(new_job) = jobs.try_recv() { next_job = Some(new_job); }
+        if let Some((scene, camera)) = next_job {
+            let (cols, rows) = camera.resolution();
+            let num_pixels = (cols * rows) as usize;
+            accumulation = Accumulator::new(num_pixels);
+            hdr_buffer.resize(num_pixels, Vec3::NULL);
+            aux_valid = false;
+            job = Some((scene, camera));
+        }
+
+        let Some((scene, camera)) = &job else { continue };
+        let resolution = camera.resolution();
+        // os passes auxiliares só mudam quando a cena ou a câmera mudam, mas são calculados de novo
+        // se o cálculo foi cancelado no meio
+        if !aux_valid || (aux_buffers.cols, aux_buffers.rows) != resolution {
+            aux_buffers = Arc::new(camera.render_aux(scene, cancel));
+            aux_valid = !cancel.load(Ordering::SeqCst);
+            if !aux_valid { continue; }
+        }
+        let first_frame = accumulation.frames == 0;
+        let mut last_publish = Instant::now();
+        camera.render(scene, &mut accumulation, &mut hdr_buffer, cancel, |_, hdr_buffer| {
+            // o primeiro frame depois de uma mudança também é mostrado enquanto fica pronto
+            if first_frame && last_publish.elapsed() >= PROGRESS_INTERVAL {
+                publish(front, hdr_buffer, &aux_buffers, resolution, 0);
+                last_publish = Instant::now();
+            }
+        });
+        if cancel.load(Ordering::SeqCst) {
+            // o frame ficou pela metade, então as amostras acumuladas não valem mais
+            accumulation = Accumulator::new(hdr_buffer.len());
+        } else {
+            // o denoiser é aplicado por quem mostra o frame (`Camera::show_frame`)
+            publish(front, &hdr_buffer, &aux_buffers, resolution, accumulation.frames);
+        }
+    }
+}
+
+// Copia a imagem pro buffer compartilhado
+fn publish(front: &Mutex<Frame>, hdr_buffer: &[Vec3], aux_buffers: &Arc<AuxBuffers>, resolution: (u32, u32), frames: u32) {
+    let mut front = front.lock().unwrap();
+    front.hdr_buffer.clear();
+    front.hdr_buffer.extend_from_slice(hdr_buffer);
+    front.aux_buffers = Arc::clone(aux_buffers);
+    front.resolution = resolution;
+    front.frames = frames;
+    front.is_new = true;
+}
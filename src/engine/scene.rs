@@ -1,17 +1,84 @@
 #![allow(dead_code)]
 
-use super::shapes::Material;
+use std::f64::consts::PI;
+use super::shapes::{Material, ShadingModel};
 use super::shapes::Shape;
-use super::{Light, Ray};
-use crate::utils::Vec3;
+use super::{Environment, Fog, Light, Ray, Sky};
+use crate::utils::{Rng, Vec3};
+
+// Deslocamento da origem dos raios de sombra ao longo da normal, pra que o raio não colida
+// com a própria superfície de onde saiu por erro de precisão ("shadow acne")
+const SHADOW_BIAS: f64 = 0.0001;
+// Quique a partir do qual o path tracing começa a terminar caminhos com a roleta russa
+const ROULETTE_START_BOUNCE: u32 = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+/// Oclusão ambiente: a luz ambiente que chega num ponto é multiplicada pela fração de `samples` raios
+/// (sorteados no hemisfério em volta da normal) que não batem em nada até a distância `max_distance`
+pub struct AmbientOcclusion {
+    pub samples: u32,
+    pub max_distance: f64,
+}
+
+impl AmbientOcclusion {
+    #[inline]
+    #[must_use]
+    pub fn new(samples: u32, max_distance: f64) -> AmbientOcclusion {
+        AmbientOcclusion { samples, max_distance }
+    }
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        AmbientOcclusion { samples: 16, max_distance: 1.0 }
+    }
+}
 
-// #[derive(Clone, PartialEq)]
 /// Armazena objetos e luzes com uma luz ambiente pré-definida
 pub struct Scene {
     pub shapes: Vec<Box<dyn Shape>>,
     pub lights: Vec<Light>,
     pub ambient_light: Vec3,
     pub bg_color: Vec3,
+    pub fog: Option<Fog>, // névoa da cena (None = sem névoa)
+    pub ambient_occlusion: Option<AmbientOcclusion>, // None = luz ambiente constante
+    pub environment: Option<Environment>, // imagem do céu (None = céu com a cor `bg_color`)
+    pub sky: Option<Sky>, // céu procedural (usado se não tiver `environment`)
+    pub night_lights: Vec<Light>, // luzes que acendem quando o céu procedural escurece (ver `update_sky`)
+    lit_night_lights: Vec<Light>, // `night_lights` com a intensidade da hora atual (vazio de dia)
+}
+
+impl Clone for Scene {
+    fn clone(&self) -> Scene {
+        Scene {
+            shapes: self.shapes.iter().map(|s| s.clone_box()).collect(),
+            lights: self.lights.clone(),
+            ambient_light: self.ambient_light,
+            bg_color: self.bg_color,
+            fog: self.fog,
+            ambient_occlusion: self.ambient_occlusion,
+            environment: self.environment.clone(),
+            sky: self.sky,
+            night_lights: self.night_lights.clone(),
+            lit_night_lights: self.lit_night_lights.clone(),
+        }
+    }
+}
+
+impl PartialEq for Scene {
+    fn eq(&self, other: &Scene) -> bool {
+        self.shapes.len() == other.shapes.len()
+            && self.shapes.iter().zip(&other.shapes).all(|(a, b)| a.eq_box(b.as_ref()))
+            && self.lights == other.lights
+            && self.ambient_light == other.ambient_light
+            && self.bg_color == other.bg_color
+            && self.fog == other.fog
+            && self.ambient_occlusion == other.ambient_occlusion
+            && self.environment == other.environment
+            && self.sky == other.sky
+            && self.night_lights == other.night_lights
+            && self.lit_night_lights == other.lit_night_lights
+    }
 }
 
 impl Scene {
@@ -19,7 +86,58 @@ impl Scene {
     #[must_use]
     /// Cria uma nova cena
     pub fn new(shapes: Vec<Box<dyn Shape>>, lights: Vec<Light>, ambient_light: Vec3, bg_color: Vec3) -> Scene {
-        Scene { shapes, lights, ambient_light, bg_color: (bg_color*255.0).clamp(0.0, 255.0) }
+        Scene { shapes, lights, ambient_light, bg_color: (bg_color*255.0).clamp(0.0, 255.0), fog: None, ambient_occlusion: None, environment: None, sky: None,
+                night_lights: Vec::new(), lit_night_lights: Vec::new() }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cor do background em espaço linear (`bg_color` é uma cor sRGB entre 0 e 255, como num color picker)
+    pub fn background(&self) -> Vec3 {
+        self.bg_color.rgb_normal().srgb_to_linear()
+    }
+
+    #[must_use]
+    /// Cor (linear) que chega por um raio de direção `dr` que não atingiu nenhum objeto
+    /// (vem do environment map, se a cena tiver um, ou do céu procedural, ou da cor do background)
+    pub fn sky_color(&self, dr: Vec3) -> Vec3 {
+        match (&self.environment, &self.sky) {
+            (Some(environment), _) => environment.sample(dr),
+            (None, Some(sky)) => sky.sample(dr),
+            (None, None) => self.background(),
+        }
+    }
+
+    /// Atualiza a cena de acordo com o céu procedural: a primeira luz direcional vira o sol (ou a lua),
+    /// a luz ambiente vira a média da cor do céu, a névoa fica com a cor do horizonte, e as `night_lights`
+    /// vão acendendo enquanto o céu escurece
+    pub fn update_sky(&mut self) {
+        let Some(sky) = self.sky else {
+            self.lit_night_lights.clear();
+            return;
+        };
+        let night = 1.0 - sky.daylight();
+        self.lit_night_lights = if night > 0.0 {
+            self.night_lights.iter().map(|l| l.clone().scaled(night)).collect()
+        } else {
+            Vec::new()
+        };
+        let sun = sky.sun_light();
+        match self.lights.iter_mut().find(|l| matches!(l, Light::Directional { .. })) {
+            Some(light) => *light = sun,
+            None => self.lights.push(sun),
+        }
+        self.ambient_light = sky.ambient_light();
+        // cor do horizonte do lado oposto ao sol
+        let sun_dr = sky.sun_direction();
+        let horizon = sky.sample(Vec3::new(-sun_dr.x, 0.0, -sun_dr.z));
+        self.bg_color = (horizon.linear_to_srgb() * 255.0).clamp(0.0, 255.0);
+        if let Some(fog) = &mut self.fog { fog.color = horizon; }
+    }
+
+    /// Luzes acesas na cena: `lights` e as `night_lights` (se for noite no céu procedural)
+    pub fn active_lights(&self) -> impl Iterator<Item = &Light> {
+        self.lights.iter().chain(&self.lit_night_lights)
     }
 
     #[inline]
@@ -44,11 +162,393 @@ impl Scene {
         self.lights.remove(i);
     }
 
-    #[allow(clippy::borrowed_box)]
-    /// Retorna a interseção com um raio de menor t ou None se não há interseção
-    pub fn get_intersection(&self, ray: &Ray) -> Option<(&Box<dyn Shape>, f64, Vec3, Material)> {
+    /// Retorna a interseção com um raio de menor t ou None se não há interseção \
+    /// (índice do objeto, t, normal, material)
+    pub fn get_intersection(&self, ray: &Ray) -> Option<(usize, f64, Vec3, Material)> {
         self.shapes.iter()
-            .filter_map(|shape| shape.get_intersection(ray).map(|(t, n, mat)| (shape, t, n, mat)) )
+            .enumerate()
+            .filter_map(|(i, shape)| shape.get_intersection(ray).map(|(t, n, mat)| (i, t, n, mat)) )
             .min_by(|(_, t1, _, _), (_, t2, _, _)| t1.total_cmp(t2) ) // pega a colisão com menor t
     }
+
+    /// Calcula a cor (entre 0 e 1) vista por um raio `ray`. \
+    /// `depth`: número máximo de reflexões/refrações que ainda podem ser geradas a partir desse raio
+    /// `rng`: gerador de números aleatórios usado pra amostrar as luzes de área
+    pub fn trace(&self, ray: &Ray, depth: u32, rng: &mut Rng) -> Vec3 {
+        self.trace_ray(ray, depth, false, rng).0
+    }
+
+    /// Mesmo que `trace`, mas também retorna o índice do objeto atingido pelo raio (`None` = background)
+    pub fn trace_hit(&self, ray: &Ray, depth: u32, rng: &mut Rng) -> (Vec3, Option<usize>) {
+        self.trace_ray(ray, depth, false, rng)
+    }
+
+    /// Mesmo que `trace_hit`, mas sabendo se o raio está dentro (`inside`) de um objeto transparente
+    fn trace_ray(&self, ray: &Ray, depth: u32, inside: bool, rng: &mut Rng) -> (Vec3, Option<usize>) {
+        // Obtém o objeto mais próximo a colidir com o raio
+        // (se o raio não colide com nenhum objeto, retorna a cor do céu)
+        let hit = self.get_intersection(ray);
+        let color = match hit {
+            None => self.sky_color(ray.dr),
+            Some(hit) => self.shade(ray, hit, depth, inside, rng),
+        };
+        (self.apply_fog(ray, hit.map(|(_, t, _, _)| t), color, rng), hit.map(|(i, _, _, _)| i))
+    }
+
+    /// Aplica a névoa da cena na cor `color` vista pelo raio `ray` (que atingiu algo em `t`, ou nada)
+    fn apply_fog(&self, ray: &Ray, t: Option<f64>, color: Vec3, rng: &mut Rng) -> Vec3 {
+        match self.fog_segment(ray, t, rng) {
+            None => color,
+            Some((transmittance, in_scattered)) => color * transmittance + in_scattered,
+        }
+    }
+
+    /// Efeito da névoa no trecho do raio `ray` até `t` (ou até o infinito, se ele não atingiu nada). \
+    /// Retorna a fração da luz que atravessa o trecho e a luz espalhada pela névoa na direção do raio,
+    /// ou `None` se a cena não tem névoa. \
+    /// (o céu já é o "fundo" da névoa, então os raios que não atingem nada não são atenuados)
+    fn fog_segment(&self, ray: &Ray, t: Option<f64>, rng: &mut Rng) -> Option<(Vec3, Vec3)> {
+        let fog = self.fog.as_ref()?;
+        let speed = ray.dr.length();
+        let dr = ray.dr / speed;
+        let distance = t.map(|t| t * speed);
+
+        let (transmittance, mut in_scattered) = match distance {
+            None => (Vec3::all(1.0), Vec3::NULL),
+            Some(distance) => {
+                let transmittance = fog.transmittance(ray.origin, dr, distance);
+                (transmittance, fog.color * (Vec3::all(1.0) - transmittance))
+            }
+        };
+
+        // Feixes de luz das spotlights: soma a luz espalhada em pontos ao longo do raio (single scattering)
+        if fog.volumetric_samples > 0 {
+            let distance = Fog::volumetric_distance(distance);
+            let step = distance / fog.volumetric_samples as f64;
+            let offset = rng.next_f64();
+            for i in 0..fog.volumetric_samples {
+                let s = (i as f64 + offset) * step;
+                let x = ray.origin + s*dr;
+                let scattering = fog.density_at(x) * step;
+                let to_camera = fog.transmittance(ray.origin, dr, s);
+                for light in self.active_lights() {
+                    if !matches!(light, Light::Spotlight { .. }) { continue; }
+                    let Some((ldr, light_intensity, _)) = light.sample(x, rng) else { continue };
+                    if self.is_shadowed(x, Vec3::NULL, ldr, false) { continue; }
+                    let to_light = fog.transmittance(x, ldr.normalized(), ldr.length());
+                    in_scattered += scattering * to_camera * to_light * light_intensity;
+                }
+            }
+        }
+
+        Some((transmittance, in_scattered))
+    }
+
+    /// Calcula a cor de um ponto atingido pelo raio `ray` (`hit` vem de `get_intersection`)
+    fn shade(&self, ray: &Ray, hit: (usize, f64, Vec3, Material), depth: u32, inside: bool, rng: &mut Rng) -> Vec3 {
+        let (_, t, n, mat) = hit;
+
+        // Calcula a cor do pixel de acordo com a iluminação
+        // intensidade da luz que chega no olho do observador (começa com a luz ambiente)
+        let p_i = ray.at(t); // ponto de interseção
+        let ambient = match (self.image_based_lighting(), &self.ambient_occlusion) {
+            // com IBL, a luz ambiente constante é trocada pela luz que vem do environment map
+            (Some(environment), _) => self.environment_light(p_i, n, ray.dr, &mat, environment.samples, rng),
+            (None, None) => mat.k_amb * self.ambient_light,
+            (None, Some(ao)) => mat.k_amb * self.ambient_light * self.ambient_occlusion(p_i, n, ray.dr, ao, rng),
+        };
+        let ieye = ambient + self.direct_light(p_i, n, ray.dr, &mat, rng);
+
+        if depth == 0 || (mat.reflectivity <= 0.0 && mat.transmission <= 0.0) { return ieye; }
+
+        let v = ray.dr.normalized();
+        let n = if n.dot(v) > 0.0 { -n } else { n }; // normal virada pro lado de onde o raio veio
+
+        // Reflexão espelhada: lança um novo raio refletido na normal
+        // (a origem é deslocada um pouco na direção da normal pra não colidir com o próprio objeto)
+        let reflected_ray = Ray::new(p_i + 0.0001*n, reflect(v, n));
+        let reflected_color = self.trace_ray(&reflected_ray, depth - 1, inside, rng).0;
+
+        // Refração: a parte transmitida é dividida entre reflexão e refração pelo termo de Fresnel (Schlick)
+        let mut transmitted_color = Vec3::NULL;
+        if mat.transmission > 0.0 {
+            transmitted_color = match refract(v, n, &mat, inside) {
+                None => reflected_color, // reflexão interna total
+                Some((fresnel, refracted_dr)) => {
+                    let refracted_ray = Ray::new(p_i - 0.0001*n, refracted_dr);
+                    let refracted_color = self.trace_ray(&refracted_ray, depth - 1, !inside, rng).0;
+                    fresnel*reflected_color + (1.0 - fresnel)*refracted_color
+                }
+            };
+        }
+
+        // Mistura a cor local com as cores refletida e transmitida
+        let k_local = (1.0 - mat.reflectivity - mat.transmission).max(0.0);
+        k_local*ieye + mat.reflectivity*reflected_color + mat.transmission*transmitted_color
+    }
+
+    /// Fração da luz ambiente que chega no ponto `p` de normal `n` (atingido por um raio de direção `ray_dr`):
+    /// lança raios em direções sorteadas (distribuição cosseno) no hemisfério em volta da normal
+    /// e conta quantos não batem em nada até `ao.max_distance`
+    fn ambient_occlusion(&self, p: Vec3, n: Vec3, ray_dr: Vec3, ao: &AmbientOcclusion, rng: &mut Rng) -> f64 {
+        if ao.samples == 0 { return 1.0; }
+        let n = if n.dot(ray_dr) > 0.0 { -n } else { n }; // hemisfério do lado de onde o raio veio
+        let origin = p + 0.0001*n;
+        let unoccluded = (0..ao.samples).filter(|_| {
+            let ray = Ray::new(origin, rng.cosine_hemisphere(n));
+            !self.shapes.iter().any(|s| s.get_intersection(&ray).is_some_and(|(t, _, _)| t < ao.max_distance))
+        }).count();
+        unoccluded as f64 / ao.samples as f64
+    }
+
+    /// Visualização de debug: retorna só a oclusão ambiente (em tons de cinza) do ponto atingido pelo raio,
+    /// e o índice do objeto atingido (usa as configurações padrão se a cena não tiver oclusão ambiente)
+    pub fn trace_ambient_occlusion(&self, ray: &Ray, rng: &mut Rng) -> (Vec3, Option<usize>) {
+        match self.get_intersection(ray) {
+            None => (Vec3::all(1.0), None),
+            Some((shape_index, t, n, _)) => {
+                let ao = self.ambient_occlusion.unwrap_or_default();
+                (Vec3::all(self.ambient_occlusion(ray.at(t), n, ray.dr, &ao, rng)), Some(shape_index))
+            }
+        }
+    }
+
+    /// Luz direta (difusa + especular de Phong) que chega no ponto `p` de normal `n`, visto na direção `view_dr`,
+    /// somando todas as luzes da cena que não estão bloqueadas por algum objeto
+    fn direct_light(&self, p: Vec3, n: Vec3, view_dr: Vec3, mat: &Material, rng: &mut Rng) -> Vec3 {
+        let mut ieye = Vec3::NULL;
+        for light in self.active_lights() {
+            // Luzes de área são amostradas em vários pontos (sombras suaves), as outras só em um
+            for _ in 0..light.samples() {
+                let Some((ldr, light_intensity, is_directional)) = light.sample(p, rng) else { continue };
+
+                // Checar se o objeto está na sombra de algum outro objeto
+                if self.is_shadowed(p, n, ldr, is_directional) { continue; }
+                // A luz também é atenuada pela névoa no caminho até `p`
+                let light_intensity = match &self.fog {
+                    None => light_intensity,
+                    Some(fog) => light_intensity * fog.light_transmittance(p, ldr, is_directional),
+                };
+
+                // Se o objeto não estiver na sombra...
+                ieye += shading(n, ldr.normalized(), view_dr, mat) * light_intensity;
+            }
+        }
+        ieye
+    }
+
+    /// Environment map que ilumina a cena (image-based lighting), se tiver um
+    fn image_based_lighting(&self) -> Option<&Environment> {
+        self.environment.as_ref().filter(|environment| environment.lighting)
+    }
+
+    /// Luz do environment map que chega no ponto `p` de normal `n`, visto na direção `view_dr`,
+    /// estimada com `samples` direções sorteadas por importância (as mais brilhantes do panorama)
+    fn environment_light(&self, p: Vec3, n: Vec3, view_dr: Vec3, mat: &Material, samples: u32, rng: &mut Rng) -> Vec3 {
+        let Some(environment) = self.image_based_lighting() else { return Vec3::NULL };
+        if samples == 0 { return Vec3::NULL; }
+        let mut ieye = Vec3::NULL;
+        for _ in 0..samples {
+            let sample = environment.sample_light(rng);
+            let (l, _, pdf) = sample;
+            if pdf <= 0.0 || self.is_shadowed(p, n, l, true) { continue; }
+            ieye += self.environment_sample(p, n, view_dr, mat, sample);
+        }
+        ieye / samples as f64
+    }
+
+    /// Contribuição de uma amostra `(l, radiance, pdf)` do environment map (ver `Environment::sample_light`). \
+    /// A iluminação da engine não tem o fator 1/π da BRDF (ver `shading`), então ele é aplicado aqui pra
+    /// que um ambiente de cor constante `c` ilumine um material difuso com `k_dif * c`, como no path tracing.
+    fn environment_sample(&self, p: Vec3, n: Vec3, view_dr: Vec3, mat: &Material, sample: (Vec3, Vec3, f64)) -> Vec3 {
+        let (l, radiance, pdf) = sample;
+        let radiance = match &self.fog {
+            None => radiance,
+            Some(fog) => radiance * fog.light_transmittance(p, l, true),
+        };
+        shading(n, l, view_dr, mat) * radiance / (PI * pdf)
+    }
+
+    /// Retorna se tem algum objeto entre o ponto `p` e a luz na direção `ldr`
+    /// (`ldr` vai de `p` até a luz, ou é só a direção da luz se ela for direcional). \
+    /// `n`: normal da superfície em `p` (`Vec3::NULL` se `p` não está numa superfície, como na névoa). A origem
+    /// do raio de sombra é deslocada ao longo da normal pro lado da luz, então o próprio objeto de `p` também
+    /// é testado (uma malha côncava faz sombra nela mesma).
+    fn is_shadowed(&self, p: Vec3, n: Vec3, ldr: Vec3, is_directional: bool) -> bool {
+        let offset = if n.dot(ldr) < 0.0 { -SHADOW_BIAS*n } else { SHADOW_BIAS*n };
+        let origin = p + offset;
+        // pras luzes pontuais, o raio vai da origem deslocada até a luz (a luz fica em t = 1)
+        let light_ray = Ray::new(origin, if is_directional { ldr } else { ldr - offset });
+        self.shapes.iter().any(|s| {
+            s.get_intersection(&light_ray)
+                .is_some_and(|(tl, _, _)| 0.0 < tl && (is_directional || tl < 1.0 - SHADOW_BIAS))
+        })
+    }
+
+    /// Calcula uma amostra da luz que chega pelo raio `ray` com path tracing (iluminação global). \
+    /// Cada chamada tem ruído, a imagem converge tirando a média de várias amostras. \
+    /// Nos pontos difusos, a luz direta é amostrada explicitamente (next-event estimation) e o caminho
+    /// continua numa direção com distribuição cosseno. Depois de alguns quiques, o caminho pode ser
+    /// terminado aleatoriamente (roleta russa). \
+    /// `max_bounces`: número máximo de quiques do caminho
+    pub fn trace_path(&self, ray: &Ray, max_bounces: u32, rng: &mut Rng) -> Vec3 {
+        let mut ray = ray.clone();
+        let mut radiance = Vec3::NULL; // luz acumulada que chega no olho
+        let mut throughput = Vec3::all(1.0); // fração da luz do próximo quique que chega no olho
+        let mut inside = false;
+        let mut diffuse_pdf = None; // densidade da direção do último quique, se ele foi difuso
+
+        for bounce in 0..=max_bounces {
+            let hit = self.get_intersection(&ray);
+            // luz espalhada e absorvida pela névoa no caminho
+            if let Some((transmittance, in_scattered)) = self.fog_segment(&ray, hit.map(|(_, t, _, _)| t), rng) {
+                radiance += throughput * in_scattered;
+                throughput *= transmittance;
+            }
+
+            // se o raio não colide com nada, a luz vem do céu (background)
+            let Some((_, t, n, mat)) = hit else {
+                // se o environment map já foi amostrado diretamente no último quique, as duas estimativas
+                // são combinadas (multiple importance sampling)
+                let weight = match (self.image_based_lighting(), diffuse_pdf) {
+                    (Some(environment), Some(pdf)) => power_heuristic(pdf, environment.light_pdf(ray.dr)),
+                    _ => 1.0,
+                };
+                radiance += throughput * self.sky_color(ray.dr) * weight;
+                break;
+            };
+
+            let p = ray.at(t);
+            let v = ray.dr.normalized();
+            let n = if n.dot(v) > 0.0 { -n } else { n }; // normal virada pro lado de onde o raio veio
+
+            // Escolhe aleatoriamente o tipo de interação, com probabilidades iguais aos pesos do material
+            let choice = rng.next_f64();
+            diffuse_pdf = None;
+            if choice < mat.reflectivity {
+                // espelho
+                ray = Ray::new(p + 0.0001*n, reflect(v, n));
+            } else if choice < mat.reflectivity + mat.transmission {
+                // vidro: escolhe entre refletir e refratar de acordo com o termo de Fresnel
+                match refract(v, n, &mat, inside) {
+                    Some((fresnel, refracted_dr)) if rng.next_f64() >= fresnel => {
+                        ray = Ray::new(p - 0.0001*n, refracted_dr);
+                        inside = !inside;
+                    }
+                    _ => { ray = Ray::new(p + 0.0001*n, reflect(v, n)); }
+                }
+            } else {
+                // difusa (ou brilhante, nos materiais Cook-Torrance metálicos)
+                radiance += throughput * self.direct_light(p, n, v, &mat, rng);
+                let glossy = mat.shading_model == ShadingModel::CookTorrance && rng.next_f64() < mat.metallic;
+                // luz do environment map: uma direção sorteada por importância, combinada com o próximo
+                // quique (que também pode sair pro céu) por multiple importance sampling
+                if let Some(environment) = self.image_based_lighting() && !glossy {
+                    let sample = environment.sample_light(rng);
+                    let (l, _, light_pdf) = sample;
+                    if light_pdf > 0.0 && !self.is_shadowed(p, n, l, true) {
+                        // no último quique não tem próximo raio pra sair pro céu, então a amostra vale sozinha
+                        let weight = if bounce == max_bounces { 1.0 } else { power_heuristic(light_pdf, n.dot(l).max(0.0) / PI) };
+                        radiance += throughput * self.environment_sample(p, n, v, &mat, sample) * weight;
+                    }
+                }
+                throughput *= mat.k_dif;
+                let dr = if glossy {
+                    // aproximação do lóbulo especular: reflexão espelhada espalhada de acordo com a rugosidade
+                    let dr = reflect(v, n) + mat.roughness * mat.roughness * rng.cosine_hemisphere(n);
+                    if dr.dot(n) > 0.0 { dr.normalized() } else { reflect(v, n) }
+                } else {
+                    let dr = rng.cosine_hemisphere(n);
+                    diffuse_pdf = Some(dr.dot(n).max(0.0) / PI);
+                    dr
+                };
+                ray = Ray::new(p + 0.0001*n, dr);
+            }
+
+            // Roleta russa: caminhos que carregam pouca luz têm mais chance de parar
+            if bounce >= ROULETTE_START_BOUNCE {
+                let survival = throughput.x.max(throughput.y).max(throughput.z).clamp(0.05, 0.95);
+                if rng.next_f64() > survival { break; }
+                throughput = throughput / survival;
+            }
+        }
+
+        radiance
+    }
+}
+
+/// Fração da luz vinda da direção unitária `l` que é refletida na direção do observador (`view_dr` é a direção
+/// do raio que chegou no ponto), multiplicada por `n·l`, de acordo com o modelo de iluminação do material
+/// (Phong ou Cook-Torrance). Segue a convenção das luzes da engine, sem o fator 1/π.
+fn shading(n: Vec3, l: Vec3, view_dr: Vec3, mat: &Material) -> Vec3 {
+    if let ShadingModel::CookTorrance = mat.shading_model {
+        return cook_torrance(n, l, -view_dr.normalized(), mat);
+    }
+
+    let mut color = Vec3::NULL;
+    let r = 2.0 * l.dot(n)*n - l; // vetor l refletido na normal
+    let nl = n.dot(l); // normal escalar l
+    let rv = r.dot(-view_dr); // r escalar v
+
+    // O check > 0.0 previne o bug de iluminação no "lado escuro da esfera"
+    if nl > 0.0 { color += mat.k_dif * nl; } // Reflexão difusa
+    if rv > 0.0 { color += mat.k_esp * rv.powf(mat.e); } // Reflexão especular
+    color
+}
+
+/// Heurística de potência do multiple importance sampling: peso de uma amostra sorteada com densidade
+/// `pdf`, quando a mesma direção também poderia ter sido sorteada pela outra estratégia com `other_pdf`
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b <= 0.0 { 0.0 } else { a / (a + b) }
+}
+
+/// BRDF de Cook-Torrance (distribuição GGX, geometria de Smith e Fresnel de Schlick) multiplicada por
+/// `n·l`, pra luz vindo da direção `l` e vista da direção `v` (as duas unitárias, saindo da superfície). \
+/// As luzes da engine seguem a convenção do Phong (sem o fator 1/π), então a BRDF é multiplicada por π.
+fn cook_torrance(n: Vec3, l: Vec3, v: Vec3, mat: &Material) -> Vec3 {
+    let nl = n.dot(l);
+    if nl <= 0.0 { return Vec3::NULL; }
+    let nv = n.dot(v).max(1e-4);
+    let h = (l + v).normalized(); // vetor "no meio do caminho" entre a luz e o observador
+    let nh = n.dot(h).max(0.0);
+    let vh = v.dot(h).max(0.0);
+
+    let alpha = (mat.roughness * mat.roughness).max(1e-4);
+    let alpha2 = alpha * alpha;
+    // distribuição das micro-facetas (GGX / Trowbridge-Reitz)
+    let d = alpha2 / (PI * (nh*nh * (alpha2 - 1.0) + 1.0).powi(2));
+    // sombreamento entre as micro-facetas (Smith com a aproximação de Schlick-GGX)
+    let k = alpha / 2.0;
+    let g = (nl / (nl*(1.0 - k) + k)) * (nv / (nv*(1.0 - k) + k));
+    // Fresnel (Schlick): dielétricos refletem ~4%, metais refletem com a cor base
+    let f0 = Vec3::all(0.04) * (1.0 - mat.metallic) + mat.k_dif * mat.metallic;
+    let f = f0 + (Vec3::all(1.0) - f0) * (1.0 - vh).powi(5);
+
+    let specular = f * (d * g / (4.0 * nl * nv));
+    let diffuse = (Vec3::all(1.0) - f) * (1.0 - mat.metallic) * mat.k_dif / PI;
+    (diffuse + specular) * PI * nl
+}
+
+/// Reflete a direção `v` na normal `n`
+fn reflect(v: Vec3, n: Vec3) -> Vec3 {
+    v - 2.0*v.dot(n)*n
+}
+
+/// Refrata a direção unitária `v` na superfície de normal `n` (virada contra `v`) de um material `mat`. \
+/// `inside`: se o raio está saindo do objeto (dentro dele) \
+/// Retorna o termo de Fresnel (Schlick) e a direção refratada, ou `None` se houve reflexão interna total.
+fn refract(v: Vec3, n: Vec3, mat: &Material, inside: bool) -> Option<(f64, Vec3)> {
+    // índices de refração do meio de onde o raio vem (n1) e para onde vai (n2)
+    let (n1, n2) = if inside { (mat.ior, 1.0) } else { (1.0, mat.ior) };
+    let eta = n1 / n2;
+    let cos_i = -n.dot(v);
+    let k = 1.0 - eta*eta*(1.0 - cos_i*cos_i);
+    if k < 0.0 { return None; }
+
+    let cos_t = k.sqrt();
+    let r0 = ((n1 - n2) / (n1 + n2)).powi(2);
+    let cos = if n1 > n2 { cos_t } else { cos_i };
+    let fresnel = r0 + (1.0 - r0)*(1.0 - cos).powi(5);
+    Some((fresnel, eta*v + (eta*cos_i - cos_t)*n))
 }
\ No newline at end of file
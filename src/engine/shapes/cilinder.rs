@@ -110,4 +110,8 @@ impl Shape for Cilinder {
     }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any { self }
+
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+
+    fn eq_box(&self, other: &dyn Shape) -> bool { (other as &dyn std::any::Any).downcast_ref::<Cilinder>() == Some(self) }
 }
\ No newline at end of file
@@ -102,4 +102,8 @@ impl Shape for Cone {
     fn material(&self) -> &Material { &self.material }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any { self }
+
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+
+    fn eq_box(&self, other: &dyn Shape) -> bool { (other as &dyn std::any::Any).downcast_ref::<Cone>() == Some(self) }
 }
\ No newline at end of file
@@ -1,17 +1,51 @@
 #![allow(dead_code)]
 use crate::utils::Vec3;
 
+#[derive(Clone, Copy, PartialEq)]
+/// Modelo de iluminação usado pra calcular a luz direta refletida por um material \
+/// `Phong`: modelo clássico (`k_dif`, `k_esp`, `e`) \
+/// `CookTorrance`: modelo fisicamente baseado (metallic/roughness, com distribuição GGX,
+/// geometria de Smith e Fresnel de Schlick), onde `k_dif` é a cor base do material
+pub enum ShadingModel {
+    Phong,
+    CookTorrance,
+}
+
+impl ShadingModel {
+    pub const ALL: [ShadingModel; 2] = [ShadingModel::Phong, ShadingModel::CookTorrance];
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShadingModel::Phong => "Phong",
+            ShadingModel::CookTorrance => "Cook-Torrance (PBR)",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 /// Material de um objeto. \
 /// `k_amb`: Coeficiente de reflexão de luz ambiente \
 /// `k_dif`: Coeficiente de reflexão difusa \
 /// `k_esp`: Coeficiente de reflexão especular \
-/// `e`: Coeficiente de "brilho" ou "polimento"
+/// `e`: Coeficiente de "brilho" ou "polimento" \
+/// `reflectivity`: Fração da cor que vem da reflexão espelhada (entre 0 e 1) \
+/// `transmission`: Fração da cor que vem da luz que atravessa o objeto (entre 0 e 1) \
+/// `ior`: Índice de refração do objeto (1.0 = ar) \
+/// `shading_model`: Modelo de iluminação do material \
+/// `metallic`: O quanto o material é metálico (entre 0 e 1, só no Cook-Torrance) \
+/// `roughness`: Rugosidade da superfície (entre 0 = polida e 1 = fosca, só no Cook-Torrance)
 pub struct Material {
     pub k_amb: Vec3,
     pub k_dif: Vec3,
     pub k_esp: Vec3,
     pub e: f64,
+    pub reflectivity: f64,
+    pub transmission: f64,
+    pub ior: f64,
+    pub shading_model: ShadingModel,
+    pub metallic: f64,
+    pub roughness: f64,
 }
 
 impl Material {
@@ -20,6 +54,12 @@ impl Material {
         k_dif: Vec3::all(0.8),
         k_esp: Vec3::all(0.8),
         e: 15.0,
+        reflectivity: 0.0,
+        transmission: 0.0,
+        ior: 1.0,
+        shading_model: ShadingModel::Phong,
+        metallic: 0.0,
+        roughness: 0.5,
     };
 
     pub const RED: Material = Material {
@@ -27,6 +67,12 @@ impl Material {
         k_dif: Vec3::new(0.8, 0.3, 0.3),
         k_esp: Vec3::new(0.8, 0.3, 0.3),
         e: 15.0,
+        reflectivity: 0.0,
+        transmission: 0.0,
+        ior: 1.0,
+        shading_model: ShadingModel::Phong,
+        metallic: 0.0,
+        roughness: 0.5,
     };
 
     pub const GREEN: Material = Material {
@@ -34,6 +80,12 @@ impl Material {
         k_dif: Vec3::new(0.3, 0.8, 0.3),
         k_esp: Vec3::new(0.3, 0.8, 0.3),
         e: 15.0,
+        reflectivity: 0.0,
+        transmission: 0.0,
+        ior: 1.0,
+        shading_model: ShadingModel::Phong,
+        metallic: 0.0,
+        roughness: 0.5,
     };
 
     pub const BLUE: Material = Material {
@@ -41,6 +93,25 @@ impl Material {
         k_dif: Vec3::new(0.3, 0.3, 0.8),
         k_esp: Vec3::new(0.3, 0.3, 0.8),
         e: 15.0,
+        reflectivity: 0.0,
+        transmission: 0.0,
+        ior: 1.0,
+        shading_model: ShadingModel::Phong,
+        metallic: 0.0,
+        roughness: 0.5,
+    };
+
+    pub const GLASS: Material = Material {
+        k_amb: Vec3::all(0.05),
+        k_dif: Vec3::all(0.05),
+        k_esp: Vec3::all(0.9),
+        e: 200.0,
+        reflectivity: 0.0,
+        transmission: 0.9,
+        ior: 1.5,
+        shading_model: ShadingModel::Phong,
+        metallic: 0.0,
+        roughness: 0.5,
     };
 
     #[inline]
@@ -51,6 +122,44 @@ impl Material {
     /// `k_esp`: Coeficiente de reflexão especular \
     /// `e`: Coeficiente de "brilho" ou "polimento"
     pub fn new(k_amb: Vec3, k_dif: Vec3, k_esp: Vec3, e: f64) -> Self {
-        Self{ k_amb, k_dif, k_esp, e }
+        Self{
+            k_amb, k_dif, k_esp, e,
+            reflectivity: 0.0, transmission: 0.0, ior: 1.0,
+            shading_model: ShadingModel::Phong, metallic: 0.0, roughness: 0.5,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Cria um novo material fisicamente baseado (Cook-Torrance). \
+    /// `base_color`: Cor base (albedo) do material, também usada como reflexão de luz ambiente \
+    /// `metallic`: O quanto o material é metálico (entre 0 e 1) \
+    /// `roughness`: Rugosidade da superfície (entre 0 e 1)
+    pub fn pbr(base_color: Vec3, metallic: f64, roughness: f64) -> Self {
+        Self{
+            k_amb: base_color, k_dif: base_color, k_esp: Vec3::NULL, e: 1.0,
+            reflectivity: 0.0, transmission: 0.0, ior: 1.0,
+            shading_model: ShadingModel::CookTorrance,
+            metallic: metallic.clamp(0.0, 1.0),
+            roughness: roughness.clamp(0.0, 1.0),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna uma cópia do material com reflexão espelhada `reflectivity` (entre 0 e 1)
+    pub fn with_reflectivity(mut self, reflectivity: f64) -> Self {
+        self.reflectivity = reflectivity.clamp(0.0, 1.0);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Retorna uma cópia do material transparente, com transmissão `transmission` (entre 0 e 1)
+    /// e índice de refração `ior`
+    pub fn with_refraction(mut self, transmission: f64, ior: f64) -> Self {
+        self.transmission = transmission.clamp(0.0, 1.0);
+        self.ior = ior;
+        self
     }
 }
\ No newline at end of file
@@ -180,15 +180,18 @@ impl Shape for Mesh {
             return None;
         }
 
+        // Os triângulos são testados dos dois lados, pra que raios refratados dentro de uma malha
+        // fechada consigam sair dela. A normal retornada sempre aponta pro lado de onde o raio veio.
         self.triangles.iter()
             .filter_map(|triangle| {
-                let normal = self.triangle_normal(triangle);
-                if normal.dot(r.dr) >= 0.0 { return None }
                 let t = self.triangle_intersects(triangle, r);
-                (t > 1e-8).then_some((t, normal))
+                (t > 1e-8).then_some((t, triangle))
             })
             .min_by(|(t1,_), (t2,_)| t1.partial_cmp(t2).unwrap())
-            .map(|(t, n)| (t, n, self.material))
+            .map(|(t, triangle)| {
+                let n = self.triangle_normal(triangle);
+                (t, n * -n.dot(r.dr).signum(), self.material)
+            })
     }
 
     fn translate(&mut self, translation_vector: Vec3) {
@@ -211,4 +214,8 @@ impl Shape for Mesh {
     }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any { self }
+
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+
+    fn eq_box(&self, other: &dyn Shape) -> bool { (other as &dyn std::any::Any).downcast_ref::<Mesh>() == Some(self) }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 #![allow(clippy::new_ret_no_self)]
 mod material;
-pub use material::Material; 
+pub use material::{Material, ShadingModel};
 mod shape;
 pub use shape::Shape;
 mod sphere;
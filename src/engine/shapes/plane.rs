@@ -64,12 +64,12 @@ impl Shape for Plane {
 
                     let uv_color = texture.sample(u, v);
 
-                    Material::new(
-                        uv_color * self.material.k_amb,
-                        uv_color * self.material.k_dif,
-                        uv_color * self.material.k_esp,
-                        self.material.e
-                    )
+                    Material {
+                        k_amb: uv_color * self.material.k_amb,
+                        k_dif: uv_color * self.material.k_dif,
+                        k_esp: uv_color * self.material.k_esp,
+                        ..self.material
+                    }
                 }
             }
         ))
@@ -86,4 +86,8 @@ impl Shape for Plane {
     fn material(&self) -> &Material { &self.material }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any { self }
+
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+
+    fn eq_box(&self, other: &dyn Shape) -> bool { (other as &dyn std::any::Any).downcast_ref::<Plane>() == Some(self) }
 }
\ No newline at end of file
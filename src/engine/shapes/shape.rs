@@ -5,7 +5,7 @@ use super::super::Ray;
 use crate::utils::{Vec3, Matrix4};
 
 /// Enum Shape que "encapsula" objetos diferentes (esfera, plano)
-pub trait Shape : Sync + Any {
+pub trait Shape : Send + Sync + Any {
     #[must_use]
     /// Retorna o material do objeto
     fn material(&self) -> &Material;
@@ -21,5 +21,13 @@ pub trait Shape : Sync + Any {
     
     fn as_any(&mut self) -> &mut dyn Any;
 
+    #[must_use]
+    /// Retorna uma cópia do objeto
+    fn clone_box(&self) -> Box<dyn Shape>;
+
+    #[must_use]
+    /// Se `other` é um objeto do mesmo tipo e igual a esse
+    fn eq_box(&self, other: &dyn Shape) -> bool;
+
     // fn rotate(&mut self, rotation_vector: Vec3);
 }
\ No newline at end of file
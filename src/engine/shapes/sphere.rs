@@ -58,12 +58,12 @@ impl Shape for Sphere {
                             let u = 0.5 + ((normal.z.atan2(normal.x) - PI/2.0) / (2.0 * -PI));
                             let v = 0.5 - (normal.y.asin() / PI);
                             let uv_color = texture.sample(u, v);
-                            (t, normal, Material::new(
-                                uv_color * self.material.k_amb,
-                                uv_color * self.material.k_dif,
-                                uv_color * self.material.k_esp,
-                                self.material.e
-                            ))
+                            (t, normal, Material {
+                                k_amb: uv_color * self.material.k_amb,
+                                k_dif: uv_color * self.material.k_dif,
+                                k_esp: uv_color * self.material.k_esp,
+                                ..self.material
+                            })
                         }
                         None => (t, normal, self.material)
                     }
@@ -86,4 +86,8 @@ impl Shape for Sphere {
     fn material(&self) -> &Material { &self.material }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any { self }
+
+    fn clone_box(&self) -> Box<dyn Shape> { Box::new(self.clone()) }
+
+    fn eq_box(&self, other: &dyn Shape) -> bool { (other as &dyn std::any::Any).downcast_ref::<Sphere>() == Some(self) }
 }
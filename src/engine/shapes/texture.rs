@@ -1,46 +1,115 @@
+#![allow(dead_code)]
+use std::sync::Arc;
+#[cfg(feature = "image")]
 use std::path::Path;
+#[cfg(feature = "image")]
 use sdl2::{rwops::RWops, image::ImageRWops, surface::Surface};
-use crate::utils::Vec3;
+use crate::utils::{load_hdr_image, Vec3};
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone)]
+/// Imagem usada como textura. As cores são guardadas já em espaço linear. \
+/// `srgb`: se a imagem original estava em sRGB (cores normais de PNG). Texturas de dados
+/// (normal maps, etc.) não são sRGB, e os valores são usados como estão. \
+/// (Os texels são guardados em f32 pra ocupar menos memória, e convertidos pra `Vec3` quando amostrados.
+/// Eles são compartilhados entre as cópias da textura, então copiar a cena não copia as imagens.)
 pub struct Texture {
-    texture_data: Vec<u8>,
+    texture_data: Arc<[[f32; 3]]>,
     pub width: u32,
     pub height: u32,
-    pub pitch: u32,
-    pub bpp: u32,
+    pub srgb: bool,
 }
 
 impl Texture {
+    /// Carrega uma textura de cor (sRGB) de um arquivo PNG
     pub fn new(file_name: &str) -> Self {
-        let surface = RWops::from_file(Path::new(file_name), "r").unwrap().load_png().unwrap();
-        Self::from_surface(surface)
+        Self::load(file_name, true)
     }
 
-    pub fn from_surface(surface: Surface) -> Self {
+    /// Carrega uma textura de dados (linear, ex: normal map) de um arquivo PNG
+    pub fn new_data(file_name: &str) -> Self {
+        Self::load(file_name, false)
+    }
+
+    /// Mesmo que `new`, mas retorna um erro em vez de entrar em pânico se o arquivo não puder ser carregado
+    pub fn try_new(file_name: &str) -> Result<Self, String> {
+        Self::try_load(file_name, true)
+    }
+
+    /// Carrega uma textura HDR (cores lineares em ponto flutuante) de um arquivo `.hdr` ou `.pfm`
+    pub fn new_hdr(file_name: &str) -> Result<Self, String> {
+        let (texture_data, width, height) = load_hdr_image(file_name).map_err(|e| e.to_string())?;
+        if width == 0 || height == 0 { return Err(format!("{file_name}: imagem vazia")); }
+        Ok(Self::from_data(width, height, texture_data))
+    }
+
+    /// Cria uma textura a partir das cores (lineares) `texture_data`, linha por linha de cima pra baixo
+    /// (a imagem não pode ser vazia)
+    pub fn from_data(width: u32, height: u32, texture_data: Vec<Vec3>) -> Self {
+        assert!(width > 0 && height > 0, "textura vazia ({width}x{height})");
+        assert_eq!(texture_data.len(), (width * height) as usize);
+        let texture_data = texture_data.iter().map(|c| to_texel(*c)).collect();
+        Self { width, height, srgb: false, texture_data }
+    }
+
+    fn load(file_name: &str, srgb: bool) -> Self {
+        Self::try_load(file_name, srgb).unwrap()
+    }
+
+    #[cfg(feature = "image")]
+    fn try_load(file_name: &str, srgb: bool) -> Result<Self, String> {
+        let surface = RWops::from_file(Path::new(file_name), "r")?.load_png()?;
+        if surface.width() == 0 || surface.height() == 0 { return Err(format!("{file_name}: imagem vazia")); }
+        Ok(Self::from_surface(surface, srgb))
+    }
+
+    // sem o SDL_image, só as texturas HDR (`new_hdr`) podem ser carregadas
+    #[cfg(not(feature = "image"))]
+    fn try_load(file_name: &str, _srgb: bool) -> Result<Self, String> {
+        Err(format!("{file_name}: carregar imagens PNG requer a feature `image`"))
+    }
+
+    #[cfg(feature = "image")]
+    /// Cria a textura a partir dos pixels de `surface`, decodificando de sRGB pra linear se `srgb` for true
+    /// (a imagem não pode ser vazia)
+    pub fn from_surface(surface: Surface, srgb: bool) -> Self {
         let width = surface.width();
         let height = surface.height();
-        let pitch = surface.pitch();
-        let bpp = pitch / width;
-        let texture_data: Vec<u8> = surface.without_lock().unwrap().to_vec();
-        Self {
-            width, height, pitch,
-            texture_data, bpp
+        assert!(width > 0 && height > 0, "textura vazia ({width}x{height})");
+        let pitch = surface.pitch() as usize;
+        let bpp = pitch / width as usize;
+        let pixels = surface.without_lock().unwrap();
+
+        let mut texture_data = Vec::with_capacity((width * height) as usize);
+        for y in 0..height as usize {
+            for x in 0..width as usize {
+                let index = y*pitch + x*bpp;
+                let color = Vec3::new(
+                    *pixels.get(index).unwrap_or(&0) as f64,
+                    *pixels.get(index+1).unwrap_or(&0) as f64,
+                    *pixels.get(index+2).unwrap_or(&0) as f64,
+                ).rgb_normal();
+                texture_data.push(to_texel(if srgb { color.srgb_to_linear() } else { color }));
+            }
         }
+        Self { width, height, srgb, texture_data: texture_data.into() }
     }
 
+    /// Retorna a cor (linear) da textura na coordenada (u, v)
     pub fn sample(&self, u: f64, v: f64) -> Vec3 {
-        let x = (u * self.width as f64).floor() as u32;
-        let y = (v * self.height as f64).floor() as u32;
-
-        let index = (y*self.pitch + x*self.bpp) as usize;
-        let r = *self.texture_data.get(index).unwrap_or(&0);
-        let g = *self.texture_data.get(index+1).unwrap_or(&0);
-        let b = *self.texture_data.get(index+2).unwrap_or(&0);
-        Vec3::new(
-            r as f64 / 255.0,
-            g as f64 / 255.0,
-            b as f64 / 255.0,
-        )
+        let x = ((u * self.width as f64).floor() as u32).min(self.width - 1);
+        let y = ((v * self.height as f64).floor() as u32).min(self.height - 1);
+        let [r, g, b] = self.texture_data[(y*self.width + x) as usize];
+        Vec3::new(r as f64, g as f64, b as f64)
     }
 }
+
+// Duas texturas são iguais se compartilham os mesmos texels (comparar as imagens pixel por pixel seria caro)
+impl PartialEq for Texture {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.texture_data, &other.texture_data) && self.srgb == other.srgb
+    }
+}
+
+fn to_texel(color: Vec3) -> [f32; 3] {
+    [color.x as f32, color.y as f32, color.z as f32]
+}
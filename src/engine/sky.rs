@@ -0,0 +1,160 @@
+use std::f64::consts::PI;
+use super::Light;
+use crate::utils::Vec3;
+
+// Inclinação da trajetória do sol em relação ao zênite (no meio-dia o sol fica a 90° - TILT do horizonte)
+const SUN_PATH_TILT: f64 = 30.0;
+// Conversão da luminância do modelo (kcd/m²) pra escala de cores da engine
+const SKY_LUMINANCE_SCALE: f64 = 0.05;
+// Intensidade da luz do sol fora da atmosfera
+const SUN_INTENSITY: f64 = 0.65;
+// Céu e luz da lua durante a noite
+const NIGHT_SKY: Vec3 = Vec3 { x: 0.002, y: 0.003, z: 0.008 };
+const MOON_LIGHT: Vec3 = Vec3 { x: 0.06, y: 0.07, z: 0.1 };
+
+#[derive(Clone, Copy, PartialEq)]
+/// Céu procedural (modelo analítico de Preetham), iluminado por um sol que se move com a hora do dia. \
+/// `time_of_day`: hora do dia (0 a 24, o sol nasce às 6h e se põe às 18h) \
+/// `turbidity`: quantidade de partículas no ar (2 = céu limpo, 10 = céu nebuloso) \
+/// `azimuth`: direção (em graus, em volta do eixo Y) de onde o sol nasce (0 = +X)
+pub struct Sky {
+    pub time_of_day: f64,
+    pub turbidity: f64,
+    pub azimuth: f64,
+}
+
+impl Sky {
+    #[inline]
+    #[must_use]
+    /// Cria um céu limpo na hora `time_of_day`
+    pub fn new(time_of_day: f64) -> Sky {
+        Sky { time_of_day, turbidity: 2.5, azimuth: 0.0 }
+    }
+
+    #[must_use]
+    /// Direção (unitária) que aponta pro sol (abaixo do horizonte durante a noite)
+    pub fn sun_direction(&self) -> Vec3 {
+        // o sol dá uma volta completa em 24h, num círculo inclinado que passa pelo leste e pelo oeste
+        let angle = PI * (self.time_of_day - 6.0) / 12.0;
+        let (sin_az, cos_az) = self.azimuth.to_radians().sin_cos();
+        let east = Vec3::new(cos_az, 0.0, -sin_az);
+        let south = Vec3::new(sin_az, 0.0, cos_az);
+        let (sin_tilt, cos_tilt) = SUN_PATH_TILT.to_radians().sin_cos();
+        angle.cos() * east + angle.sin() * (cos_tilt * Vec3::Y + sin_tilt * south)
+    }
+
+    #[must_use]
+    /// Cor (linear) do céu vista na direção `dr`
+    pub fn sample(&self, dr: Vec3) -> Vec3 {
+        let sun = self.sun_direction();
+        let day = self.daylight();
+        let mut color = NIGHT_SKY * (1.0 - day);
+        if day > 0.0 {
+            // o modelo só vale com o sol acima do horizonte, então no crepúsculo ele é usado com o sol
+            // no horizonte, e vai apagando
+            let sun = Vec3::new(sun.x, sun.y.max(0.01), sun.z).normalized();
+            let dr = dr.normalized();
+            // abaixo do horizonte, usa a cor do horizonte
+            let dr = Vec3::new(dr.x, dr.y.max(0.001), dr.z).normalized();
+            color += self.preetham(dr, sun) * day;
+        }
+        color
+    }
+
+    #[must_use]
+    /// Luz direcional do sol (ou da lua, durante a noite), com a cor filtrada pela atmosfera
+    pub fn sun_light(&self) -> Light {
+        let sun = self.sun_direction();
+        if sun.y > 0.0 {
+            // o sol vai apagando enquanto se põe (até uns 3° acima do horizonte)
+            let x = (sun.y.asin().to_degrees() / 3.0).min(1.0);
+            Light::directional(-sun, self.sun_color() * (x * x * (3.0 - 2.0*x)), SUN_INTENSITY)
+        } else {
+            // a lua fica do lado oposto do sol
+            Light::directional(sun, MOON_LIGHT * (1.0 - self.daylight()), 1.0)
+        }
+    }
+
+    #[must_use]
+    /// Luz ambiente: média da cor do céu no hemisfério de cima (ponderada pelo cosseno)
+    pub fn ambient_light(&self) -> Vec3 {
+        const RINGS: u32 = 4;
+        const SEGMENTS: u32 = 8;
+        let mut sum = Vec3::NULL;
+        let mut weight = 0.0;
+        for i in 0..RINGS {
+            let elevation = (i as f64 + 0.5) / RINGS as f64 * PI / 2.0;
+            for j in 0..SEGMENTS {
+                let az = j as f64 / SEGMENTS as f64 * 2.0 * PI;
+                let dr = Vec3::new(elevation.cos() * az.cos(), elevation.sin(), elevation.cos() * az.sin());
+                // peso = cosseno com a normal (Y) * área do anel
+                let w = elevation.sin() * elevation.cos();
+                sum += self.sample(dr) * w;
+                weight += w;
+            }
+        }
+        sum / weight
+    }
+
+    #[must_use]
+    /// Quanto o céu está iluminado pelo sol: 1 de dia, 0 de noite, com uma transição suave
+    /// enquanto o sol está até 6° abaixo do horizonte (crepúsculo)
+    pub fn daylight(&self) -> f64 {
+        let elevation = self.sun_direction().y.asin().to_degrees();
+        let x = ((elevation + 6.0) / 8.0).clamp(0.0, 1.0);
+        x * x * (3.0 - 2.0*x)
+    }
+
+    // Cor do sol depois de atravessar a atmosfera: o caminho é mais longo perto do horizonte,
+    // onde o azul é mais espalhado e a luz fica laranja/vermelha
+    fn sun_color(&self) -> Vec3 {
+        let sun = self.sun_direction();
+        let zenith_angle = sun.y.clamp(0.0, 1.0).acos().to_degrees();
+        // massa de ar (Kasten e Young)
+        let air_mass = 1.0 / (zenith_angle.to_radians().cos() + 0.50572 * (96.07995 - zenith_angle).powf(-1.6364));
+        let rayleigh = Vec3::new(0.03, 0.07, 0.16);
+        let aerosol = 0.015 * self.turbidity;
+        let transmittance = |beta: f64| (-air_mass * (beta + aerosol)).exp();
+        Vec3::new(transmittance(rayleigh.x), transmittance(rayleigh.y), transmittance(rayleigh.z))
+    }
+
+    // Modelo de Preetham: luminância e cromaticidade (Yxy) do céu na direção `dr`, com o sol em `sun`
+    fn preetham(&self, dr: Vec3, sun: Vec3) -> Vec3 {
+        let t = self.turbidity;
+        let theta_s = sun.y.clamp(-1.0, 1.0).acos(); // ângulo do sol com o zênite
+        let theta = dr.y.clamp(-1.0, 1.0).acos(); // ângulo da direção com o zênite
+        let gamma = dr.dot(sun).clamp(-1.0, 1.0).acos(); // ângulo entre a direção e o sol
+
+        // Distribuição de Perez: como a luminância varia em volta do zênite e do sol
+        let perez = |[a, b, c, d, e]: [f64; 5], theta: f64, gamma: f64| {
+            (1.0 + a * (b / theta.cos().max(0.001)).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+        };
+        let coef_y = [0.1787*t - 1.4630, -0.3554*t + 0.4275, -0.0227*t + 5.3251, 0.1206*t - 2.5771, -0.0670*t + 0.3703];
+        let coef_x = [-0.0193*t - 0.2592, -0.0665*t + 0.0008, -0.0004*t + 0.2125, -0.0641*t - 0.8989, -0.0033*t + 0.0452];
+        let coef_yc = [-0.0167*t - 0.2608, -0.0950*t + 0.0092, -0.0079*t + 0.2102, -0.0441*t - 1.6537, -0.0109*t + 0.0529];
+
+        // valores no zênite
+        let chi = (4.0/9.0 - t/120.0) * (PI - 2.0*theta_s);
+        let zenith_y = ((4.0453*t - 4.9710) * chi.tan() - 0.2155*t + 2.4192).max(0.0);
+        let (t1, t2, t3) = (theta_s, theta_s*theta_s, theta_s*theta_s*theta_s);
+        let zenith_x = t*t * (0.00166*t3 - 0.00375*t2 + 0.00209*t1)
+            + t * (-0.02903*t3 + 0.06377*t2 - 0.03202*t1 + 0.00394)
+            + (0.11693*t3 - 0.21196*t2 + 0.06052*t1 + 0.25886);
+        let zenith_yc = t*t * (0.00275*t3 - 0.00610*t2 + 0.00317*t1)
+            + t * (-0.04214*t3 + 0.08970*t2 - 0.04153*t1 + 0.00516)
+            + (0.15346*t3 - 0.26756*t2 + 0.06670*t1 + 0.26688);
+
+        let value = |zenith: f64, coef: [f64; 5]| zenith * perez(coef, theta, gamma) / perez(coef, 0.0, theta_s);
+        let luminance = value(zenith_y, coef_y) * SKY_LUMINANCE_SCALE;
+        let (x, y) = (value(zenith_x, coef_x), value(zenith_yc, coef_yc));
+
+        // Yxy -> XYZ -> RGB linear (primárias do sRGB)
+        let cx = x / y * luminance;
+        let cz = (1.0 - x - y) / y * luminance;
+        Vec3::new(
+            3.2406*cx - 1.5372*luminance - 0.4986*cz,
+            -0.9689*cx + 1.8758*luminance + 0.0415*cz,
+            0.0557*cx - 0.2040*luminance + 1.0570*cz,
+        ).clamp(0.0, f64::INFINITY)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::utils::Vec3;
+
+#[derive(Clone, Copy, PartialEq)]
+/// Operador que converte as cores HDR (sem limite de intensidade) do framebuffer
+/// pra faixa [0, 1] que dá pra mostrar na tela. \
+/// `Clamp`: só corta tudo acima de 1 (estoura os brilhos) \
+/// `Reinhard`: `c / (1 + c)`, comprime os brilhos suavemente \
+/// `Aces`: curva "filmic" do ACES (aproximação de Narkowicz), mais contraste que o Reinhard
+pub enum ToneMapping {
+    Clamp,
+    Reinhard,
+    Aces,
+}
+
+impl ToneMapping {
+    pub const ALL: [ToneMapping; 3] = [ToneMapping::Clamp, ToneMapping::Reinhard, ToneMapping::Aces];
+
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToneMapping::Clamp => "Clamp",
+            ToneMapping::Reinhard => "Reinhard",
+            ToneMapping::Aces => "ACES filmic",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Aplica a exposição `exposure` (em stops: cada +1 dobra o brilho) e o operador na cor HDR `color`.
+    /// Retorna uma cor entre 0 e 1.
+    pub fn apply(&self, color: Vec3, exposure: f64) -> Vec3 {
+        let c = color * 2.0_f64.powf(exposure);
+        match self {
+            ToneMapping::Clamp => c,
+            ToneMapping::Reinhard => c / (Vec3::all(1.0) + c.clamp(0.0, f64::INFINITY)),
+            ToneMapping::Aces => {
+                let (a, b, c2, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+                (c * (a*c + Vec3::all(b))) / (c * (c2*c + Vec3::all(d)) + Vec3::all(e))
+            }
+        }.clamp(0.0, 1.0)
+    }
+}
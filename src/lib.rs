@@ -0,0 +1,9 @@
+//! Ray tracer da disciplina de Computação Gráfica 1. \
+//! O núcleo (cenas, câmera, formas, luzes e a matemática) não depende do SDL: a janela com a interface
+//! (feature `window`) fica no binário, e o carregamento de texturas PNG pelo SDL_image fica na feature `image`.
+pub mod engine;
+pub mod scenes;
+pub mod utils;
+
+pub use engine::{Camera, Light, Scene};
+pub use utils::{Matrix3, Matrix4, Vec3, Vec4};
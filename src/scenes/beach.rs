@@ -4,8 +4,6 @@ use std::io::BufReader;
 use std::path::Path;
 
 use obj::Obj;
-use sdl2::rwops::RWops;
-use sdl2::image::ImageRWops;
 
 use crate::engine::{Scene, Light};
 use crate::engine::camera::Camera;
@@ -13,7 +11,7 @@ use crate::utils::transform::{rotation_around_axis, scale_matrix, shear_matrix_y
 use crate::utils::Vec3;
 use crate::engine::shapes::{Cilinder, Cone, Material, Mesh, Plane, Sphere, Texture};
 
-pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {    
+pub fn beach() -> (Scene, Camera, u32, u32) {    
     let sand_pc = Vec3::new(0.0, 0.0, 9.0); // Ponto conhecido do plano
     let sand_normal = Vec3::new(0.0, 1.0 , 0.0001); // Normal do plano
     // let sand_material = Material::new(
@@ -42,7 +40,7 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
         Vec3::new(0.9, 0.9, 0.9), // Diffuse
         Vec3::new(0.9, 0.9, 0.9), // Specular
         50.0, // coeficiente de "brilho" ou "polimento"
-    );
+    ).with_reflectivity(0.3); // a água reflete o céu e os objetos
 
     let snowball_material = Material::new(
         Vec3::all(0.9),
@@ -304,7 +302,7 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
         Vec3::new(0.8, 0.95, 0.7),
         Vec3::new(0.8, 0.95, 0.7),
         40.0,
-    );
+    ).with_refraction(0.6, 1.33); // chá é praticamente água
     let liquid = Cilinder::new(
         0.064, 0.18,
         Vec3::new(umbrella_pole_cb.x - td/2.0, 0.7, umbrella_pole_cb.z - 1.0),
@@ -312,8 +310,8 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
         tea_material, true, true
     );
 
-    let sand_texture = Texture::new("textures/sand.png");
-    let water_texture = Texture::new("textures/water.png");
+    let sand_texture = super::load_texture("textures/sand.png");
+    let water_texture = super::load_texture("textures/water.png");
 
     // snowman 2
     let snowman2_feet_center = Vec3::new(13.5, -0.10, 1.0);
@@ -389,7 +387,7 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
         10.0, // coeficiente de "brilho" ou "polimento"
     );
 
-    let ball_texture = Texture::new("textures/beach_ball.png");
+    let ball_texture = super::load_texture("textures/beach_ball.png");
 
     let lightpole_cilinder_1 = Cilinder::new(
         0.1, 6.5,
@@ -422,8 +420,8 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
     
     // Criando os objetos e as luzes
     let shapes = vec![
-        Plane::new( sand_pc, sand_normal, sand_material, Some(sand_texture), 4.0, 4.0 ), // chão
-        Plane::new( water_pc, water_normal, water_material, Some(water_texture), 16.0, 16.0 ), // fundo
+        Plane::new( sand_pc, sand_normal, sand_material, sand_texture, 4.0, 4.0 ), // chão
+        Plane::new( water_pc, water_normal, water_material, water_texture, 16.0, 16.0 ), // fundo
 
         lightpole_cilinder_1,
         lightpole_sphere,
@@ -431,7 +429,7 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
         lightpole_cone,
 
         // bola de praia
-        Sphere::new( ball_center, ball_radius, ball_material, Some(ball_texture) ),
+        Sphere::new( ball_center, ball_radius, ball_material, ball_texture ),
 
         // snowman 1
         Sphere::new( snowman1_feet_center, snowman1_feet_radius, snowball_material, None ), // pé
@@ -500,7 +498,13 @@ pub fn beach<'a>() -> (Scene, Camera<'a>, u32, u32) {
     let ambient_light = Vec3::all(0.4); // Luz ambiente
     let bg_color = Vec3::new(0.35,0.63,0.95); // cor do background
 
-    let scene = Scene::new(shapes, lights, ambient_light, bg_color);
+    let mut scene = Scene::new(shapes, lights, ambient_light, bg_color);
+    // com o céu procedural (ligado na interface), à noite acendem um poste de luz e uma lâmpada embaixo do guarda-sol
+    let night_light_color = Vec3::new(1.0, 0.8, 0.3);
+    scene.night_lights = vec![
+        Light::spotlight(Vec3::new(14.0, 6.25, 4.0), -Vec3::Y, 45.0, night_light_color, 0.65),
+        Light::point(umbrella_top_cb + umbrella_top_height * Vec3::Y * 0.9, night_light_color, 0.3),
+    ];
 
     let p0 = Vec3::new(2.3, 1.3, 3.9); // posição do observador
     let aspect_ratio: f64 = 16.0/9.0; // aspect ratio que eu quero
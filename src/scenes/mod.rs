@@ -13,3 +13,28 @@ pub use sphere_test::sphere_test;
 pub use simple::simple;
 pub use cube::cube;
 
+use crate::engine::Scene;
+use crate::engine::camera::Camera;
+use crate::engine::shapes::Texture;
+
+/// Carrega a textura de cor `file_name`, ou retorna `None` (o objeto fica só com a cor do material) se ela
+/// não puder ser carregada, ex: sem a feature `image`
+fn load_texture(file_name: &str) -> Option<Texture> {
+    Texture::try_new(file_name).inspect_err(|e| eprintln!("textura ignorada: {e}")).ok()
+}
+
+/// Nomes das cenas que podem ser escolhidas pela linha de comando
+pub const NAMES: [&str; 6] = ["beach", "cone_test", "cilinder_test", "sphere_test", "simple", "cube"];
+
+/// Cria a cena de nome `name` (ver `NAMES`), ou `None` se não existir uma cena com esse nome
+pub fn by_name(name: &str) -> Option<(Scene, Camera, u32, u32)> {
+    match name {
+        "beach" => Some(beach()),
+        "cone_test" => Some(cone_test()),
+        "cilinder_test" => Some(cilinder_test()),
+        "sphere_test" => Some(sphere_test()),
+        "simple" => Some(simple()),
+        "cube" => Some(cube()),
+        _ => None,
+    }
+}
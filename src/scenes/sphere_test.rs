@@ -1,14 +1,12 @@
 #![allow(unused_variables)]
 use std::path::Path;
-use sdl2::image::ImageRWops;
-use sdl2::rwops::RWops;
 use crate::engine::{Scene, Light};
 use crate::engine::camera::Camera;
 use crate::utils::transform::householder_reflection;
 use crate::utils::Vec3;
 use crate::engine::shapes::{Cilinder, Cone, Material, Plane, Sphere, Texture};
 
-pub fn sphere_test<'a>() -> (Scene, Camera<'a>, u32, u32) {    
+pub fn sphere_test() -> (Scene, Camera, u32, u32) {    
     // Definindo as propriedades de cada objeto
     let sphere1_radius = 0.5; // Raio em metros
     let sphere1_center = Vec3::new(2.0, 0.25, -2.0); // Coords. centro da esfera (metros)
@@ -42,8 +40,8 @@ pub fn sphere_test<'a>() -> (Scene, Camera<'a>, u32, u32) {
     let light1_color = Vec3::new(1.0, 1.0, 1.0);
     let light1_intensity = 1.0;
     
-    let my_texture = Texture::new("textures/beach_ball.png");
-    let plane_texture = Texture::new("textures/sand.png");
+    let my_texture = super::load_texture("textures/beach_ball.png");
+    let plane_texture = super::load_texture("textures/sand.png");
     
     let reflect_matrix = householder_reflection(Vec3::NULL, (Vec3::X - Vec3::Z).normalized());
     let mut ball2_center = sphere1_center;
@@ -74,10 +72,10 @@ pub fn sphere_test<'a>() -> (Scene, Camera<'a>, u32, u32) {
 
     // Criando os objetos e as luzes
     let shapes = vec![
-        Plane::new( plane1_pc, plane1_normal, sphere1_material, Some(plane_texture), 2.0, 2.0 ),
+        Plane::new( plane1_pc, plane1_normal, sphere1_material, plane_texture, 2.0, 2.0 ),
         Plane::new( plane2_pc, plane2_normal, plane2_material, None, 1.0, 1.0 ),
-        Sphere::new( sphere1_center, sphere1_radius, sphere1_material, Some(my_texture.clone()) ),
-        Sphere::new( ball2_center, sphere1_radius, sphere1_material, Some(my_texture) ),
+        Sphere::new( sphere1_center, sphere1_radius, sphere1_material, my_texture.clone() ),
+        Sphere::new( ball2_center, sphere1_radius, sphere1_material, my_texture ),
         cilinder_x, cilinder_y, cilinder_z
     ];
     
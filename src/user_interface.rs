@@ -4,12 +4,13 @@
 #![allow(static_mut_refs)]
 use imgui::{TreeNodeFlags, Ui};
 
-use crate::engine::{Scene, Light};
-use crate::engine::camera::Camera;
-use crate::utils::{save_surface_as_ppm, transform::*};
-use crate::utils::Matrix4;
-use crate::utils::Vec3;
-use crate::engine::shapes::{Cilinder, Cone, Material, Mesh, Plane, Sphere};
+use aula1_rust::engine::{AmbientOcclusion, Attenuation, Environment, EnvironmentMap, Fog, Scene, Light, Sky, ToneMapping};
+use aula1_rust::engine::camera::{Camera, RenderMode, SamplePattern};
+use aula1_rust::engine::{Denoiser, RenderPass};
+use aula1_rust::utils::{save_hdr_as_pfm, save_image_as_ppm, transform::*};
+use aula1_rust::utils::Matrix4;
+use aula1_rust::utils::Vec3;
+use aula1_rust::engine::shapes::{Cilinder, Cone, Material, Mesh, Plane, ShadingModel, Sphere};
 
 static mut TRANSFORMATION_TYPE: i32 = 0;
 static mut TRANSLATION: [f32; 3] = [0.0, 0.0, 0.0];
@@ -29,6 +30,8 @@ static mut UP: [f32; 3] = [0.0, 1.0, 0.0];
 static mut RESOLUTION: [u32; 2] = [960, 540];
 static mut SELECTED_MATERIAL: Material = Material::WHITE;
 static mut PIVOT: [f32; 3] = [0.0, 0.0, 0.0];
+static mut ENVIRONMENT_PATH: String = String::new();
+static mut ENVIRONMENT_ERROR: String = String::new();
 
 
 pub fn make_transformation_menu(ui: &Ui) {
@@ -141,6 +144,20 @@ fn mod_dr(ui: &Ui, label: String, dr: &mut Vec3, p0: Option<Vec3>) -> bool {
     changed
 }
 
+fn mod_attenuation(ui: &Ui, label: String, attenuation: &mut Attenuation) -> bool {
+    let mut changed = false;
+    let mut vec = [attenuation.constant as f32, attenuation.linear as f32, attenuation.quadratic as f32];
+    if ui.input_float3(label.clone() + "Atenuação (const., lin., quad.)", &mut vec).enter_returns_true(true).build() {
+        *attenuation = Attenuation::new(vec[0] as f64, vec[1] as f64, vec[2] as f64);
+        changed = true;
+    }
+    ui.same_line();
+    if ui.small_button(label.clone() + "sem atenuação") { *attenuation = Attenuation::NONE; changed = true; }
+    ui.same_line();
+    if ui.small_button(label + "1/d²") { *attenuation = Attenuation::INVERSE_SQUARE; changed = true; }
+    changed
+}
+
 fn mod_double(ui: &Ui, label: String, d: &mut f64) -> bool {
     let mut v = *d as f32;
     if ui.input_float(label, &mut v).enter_returns_true(true).build() {
@@ -200,7 +217,9 @@ fn create_light_menu(ui: &Ui, scene: &mut Scene) {
                 pos: Vec3::NULL,
                 dr: Vec3::Y,
                 angle: 22.5,
+                inner_angle: 15.0,
                 intensity: Vec3::all(1.0),
+                attenuation: Attenuation::NONE,
             });
         }
         if ui.button("Directional Light") {
@@ -213,6 +232,33 @@ fn create_light_menu(ui: &Ui, scene: &mut Scene) {
             scene.add_light(Light::Point {
                 pos: Vec3::NULL,
                 intensity: Vec3::all(1.0),
+                attenuation: Attenuation::NONE,
+            });
+        }
+        if ui.button("Rectangle Light") {
+            scene.add_light(Light::Rectangle {
+                pos: Vec3::NULL,
+                u: Vec3::X,
+                v: Vec3::Z,
+                intensity: Vec3::all(1.0),
+                samples: 16,
+            });
+        }
+        if ui.button("Disk Light") {
+            scene.add_light(Light::Disk {
+                pos: Vec3::NULL,
+                dr: Vec3::Y,
+                radius: 0.5,
+                intensity: Vec3::all(1.0),
+                samples: 16,
+            });
+        }
+        if ui.button("Sphere Light") {
+            scene.add_light(Light::Sphere {
+                pos: Vec3::NULL,
+                radius: 0.5,
+                intensity: Vec3::all(1.0),
+                samples: 16,
             });
         }
     }
@@ -271,12 +317,25 @@ fn material_menu(ui: &Ui) {
         Material::RED => " RED",
         Material::GREEN => " GREEN",
         Material::BLUE => " BLUE",
+        Material::GLASS => " GLASS",
         _ => ""
     });
     if ui.button("WHITE") { material = Material::WHITE; }
     if ui.button("RED") { material = Material::RED; }
     if ui.button("GREEN") { material = Material::GREEN; }
     if ui.button("BLUE") { material = Material::BLUE; }
+    if ui.button("GLASS") { material = Material::GLASS; }
+    let mut shading_model = ShadingModel::ALL.iter().position(|m| *m == material.shading_model).unwrap();
+    if ui.combo("Shading model", &mut shading_model, &ShadingModel::ALL, |m| m.name().into()) {
+        material.shading_model = ShadingModel::ALL[shading_model];
+    }
+    if let ShadingModel::CookTorrance = material.shading_model {
+        let mut metallic_roughness = [material.metallic as f32, material.roughness as f32];
+        if ui.input_float2("Metallic, Roughness", &mut metallic_roughness).enter_returns_true(true).build() {
+            material.metallic = metallic_roughness[0].clamp(0.0, 1.0) as f64;
+            material.roughness = metallic_roughness[1].clamp(0.0, 1.0) as f64;
+        }
+    }
     unsafe { SELECTED_MATERIAL = material; }
 }
 
@@ -286,8 +345,18 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
     .size([400.0, 540.0], imgui::Condition::FirstUseEver)
     .position([0.0, 0.0], imgui::Condition::FirstUseEver)
     .build(|| {
-        if ui.button("make day") { make_day(scene); }
-        if ui.button("make night") { make_night(scene); }
+        // Céu procedural: a hora do dia move o sol, e a cor do céu e a luz ambiente vêm do modelo
+        let mut sky_enabled = scene.sky.is_some();
+        if ui.checkbox("Céu procedural", &mut sky_enabled) {
+            scene.sky = sky_enabled.then(|| Sky::new(12.0));
+            scene.update_sky();
+        }
+        if let Some(sky) = &mut scene.sky {
+            let mut changed = ui.slider("Hora do dia", 0.0, 24.0, &mut sky.time_of_day);
+            changed |= ui.slider("Turbidez", 1.7, 10.0, &mut sky.turbidity);
+            changed |= ui.slider("Azimute do sol", -180.0, 180.0, &mut sky.azimuth);
+            if changed { scene.update_sky(); }
+        }
 
         create_light_menu(ui, scene);
         create_shape_menu(ui, scene);
@@ -303,6 +372,11 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
                 camera.set_viewport_size(whf[0] as f64, whf[1] as f64);
                 camera.set_focal_distance(whf[2] as f64);
             }
+            let mut lens = [camera.aperture as f32, camera.focus_distance as f32];
+            if ui.input_float2("Aperture radius, Focus distance", &mut lens).enter_returns_true(true).build() {
+                camera.aperture = lens[0].max(0.0) as f64;
+                camera.focus_distance = lens[1] as f64;
+            }
             let mut oblique = [camera.obliqueness.x as f32, camera.obliqueness.y as f32, camera.obliqueness.z as f32];
             if ui.input_float3("Oblique angle", &mut oblique).enter_returns_true(true).build() {
                 camera.obliqueness = Vec3::new(oblique[0] as f64, oblique[1] as f64, oblique[2] as f64);
@@ -310,6 +384,54 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
             unsafe { if ui.input_scalar_n("Resolution", &mut RESOLUTION).enter_returns_true(true).build() {
                 camera.set_resolution(RESOLUTION[0], RESOLUTION[1]);
             }}
+            let mut mode = RenderMode::ALL.iter().position(|m| *m == camera.render_mode).unwrap();
+            if ui.combo("Render mode", &mut mode, &RenderMode::ALL, |m| m.name().into()) {
+                camera.set_render_mode(RenderMode::ALL[mode]);
+            }
+            ui.checkbox("Progressive refinement", &mut camera.progressive_refinement);
+            ui.input_scalar("Max accumulated frames (0 = no limit)", &mut camera.max_refinement_frames).build();
+            ui.text(format!("Accumulated frames: {}", camera.accumulated_frames));
+            ui.input_scalar("Max reflection depth", &mut camera.max_depth).build();
+            ui.input_scalar("Max path bounces", &mut camera.max_bounces).build();
+            // a grade de amostras é quadrada, então o valor é arredondado pro próximo quadrado perfeito
+            if ui.input_scalar("Samples per pixel", &mut camera.samples_per_pixel).enter_returns_true(true).build() {
+                camera.samples_per_pixel = SamplePattern::sample_count(camera.samples_per_pixel);
+            }
+            let mut pattern = SamplePattern::ALL.iter().position(|p| *p == camera.sample_pattern).unwrap();
+            if ui.combo("Sample pattern", &mut pattern, &SamplePattern::ALL, |p| p.name().into()) {
+                camera.sample_pattern = SamplePattern::ALL[pattern];
+            }
+            let mut tone_mapping = ToneMapping::ALL.iter().position(|t| *t == camera.tone_mapping).unwrap();
+            if ui.combo("Tone mapping", &mut tone_mapping, &ToneMapping::ALL, |t| t.name().into()) {
+                camera.tone_mapping = ToneMapping::ALL[tone_mapping];
+                camera.update_image();
+            }
+            if mod_double(ui, "Exposure (stops)".to_string(), &mut camera.exposure) { camera.update_image(); }
+            let mut pass = RenderPass::ALL.iter().position(|p| *p == camera.render_pass).unwrap();
+            if ui.combo("Render pass (view)", &mut pass, &RenderPass::ALL, |p| p.name().into()) {
+                camera.render_pass = RenderPass::ALL[pass];
+                camera.update_image();
+            }
+            let mut denoise = camera.denoiser.is_some();
+            // o denoiser só filtra a imagem pronta, então mudar ele não descarta as amostras acumuladas
+            let mut denoiser_changed = false;
+            if ui.checkbox("Denoiser (à-trous)", &mut denoise) {
+                camera.denoiser = denoise.then(Denoiser::default);
+                denoiser_changed = true;
+            }
+            if let Some(denoiser) = &mut camera.denoiser {
+                denoiser_changed |= ui.input_scalar("Denoiser iterations", &mut denoiser.iterations).build();
+                denoiser_changed |= mod_double(ui, "Color sigma".to_string(), &mut denoiser.color_sigma);
+                denoiser_changed |= mod_double(ui, "Normal power".to_string(), &mut denoiser.normal_power);
+                denoiser_changed |= mod_double(ui, "Depth sigma".to_string(), &mut denoiser.depth_sigma);
+                denoiser_changed |= mod_double(ui, "Albedo sigma".to_string(), &mut denoiser.albedo_sigma);
+            }
+            if denoiser_changed { camera.apply_denoiser(); }
+            ui.checkbox("Adaptive anti-aliasing", &mut camera.adaptive_aa);
+            if camera.adaptive_aa {
+                mod_double(ui, "Contrast threshold".to_string(), &mut camera.contrast_threshold);
+                ui.input_scalar("Max subdivision", &mut camera.max_subdivision).build();
+            }
 
             if ui.small_button("reset rotation") { camera.set_coord_system([-Vec3::X, Vec3::Y, -Vec3::Z]); }
             
@@ -320,7 +442,18 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
             }};
 
             if ui.small_button("save image") {
-                save_surface_as_ppm(&camera.sdl_surface, "output.ppm").unwrap();
+                save_image_as_ppm(&camera.image, "output.ppm").unwrap();
+            }
+            ui.same_line();
+            if ui.small_button("save HDR image") {
+                save_hdr_as_pfm(&camera.hdr_image(), "output.pfm").unwrap();
+            }
+            ui.same_line();
+            // salva cada passe auxiliar num .pfm separado (output_depth.pfm, output_normal.pfm, ...)
+            if ui.small_button("save aux passes") {
+                for pass in &RenderPass::ALL[1..] {
+                    camera.aux_buffers().save_pass(*pass, &format!("output_{}.pfm", pass.file_suffix())).unwrap();
+                }
             }
 
             ui.text(format!("Camera coord system:\nX: {:.2?}\nY: {:.2?}\nZ: {:.2?}", camera.coord_system[0], camera.coord_system[1], camera.coord_system[2]));
@@ -335,25 +468,95 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
             }
         }
         
+        // Menu de oclusão ambiente
+        if ui.collapsing_header("Oclusão ambiente", TreeNodeFlags::empty()) {
+            let mut enabled = scene.ambient_occlusion.is_some();
+            if ui.checkbox("Oclusão ambiente ativada", &mut enabled) {
+                scene.ambient_occlusion = enabled.then(AmbientOcclusion::default);
+            }
+            if let Some(ao) = &mut scene.ambient_occlusion {
+                ui.input_scalar("Amostras de AO", &mut ao.samples).build();
+                mod_double(ui, "Distância máxima de AO".to_string(), &mut ao.max_distance);
+            }
+        }
+
+        // Menu do ambiente (environment map)
+        if ui.collapsing_header("Ambiente", TreeNodeFlags::empty()) {
+            unsafe {
+                ui.input_text("Arquivo (panorama .png/.hdr/.pfm) ou pasta (cube map)", &mut ENVIRONMENT_PATH).build();
+                let mut loaded = None;
+                if ui.small_button("carregar panorama") { loaded = Some(Environment::load_equirectangular(&ENVIRONMENT_PATH)); }
+                ui.same_line();
+                if ui.small_button("carregar cube map") { loaded = Some(Environment::load_cube_map(&ENVIRONMENT_PATH)); }
+                ui.same_line();
+                if ui.small_button("remover") { scene.environment = None; }
+                match loaded {
+                    Some(Ok(environment)) => { scene.environment = Some(environment); ENVIRONMENT_ERROR.clear(); }
+                    Some(Err(e)) => { ENVIRONMENT_ERROR = e; }
+                    None => {}
+                }
+                if !ENVIRONMENT_ERROR.is_empty() { ui.text(format!("Erro: {ENVIRONMENT_ERROR}")); }
+            }
+            if let Some(environment) = &mut scene.environment {
+                ui.text(match environment.map {
+                    EnvironmentMap::Equirectangular(_) => "Panorama equiretangular",
+                    EnvironmentMap::CubeMap(_) => "Cube map",
+                });
+                let mut degrees = environment.rotation.to_degrees();
+                if mod_double(ui, "Rotação (graus)".to_string(), &mut degrees) { environment.rotation = degrees.to_radians(); }
+                mod_double(ui, "Intensidade".to_string(), &mut environment.intensity);
+                ui.checkbox("Iluminar a cena (IBL)", &mut environment.lighting);
+                if environment.lighting {
+                    ui.input_scalar("Amostras do ambiente (Whitted)", &mut environment.samples).build();
+                }
+            } else {
+                ui.text("Sem environment map (céu com a cor do background).");
+            }
+        }
+
+        // Menu de névoa
+        if ui.collapsing_header("Névoa", TreeNodeFlags::empty()) {
+            let mut enabled = scene.fog.is_some();
+            if ui.checkbox("Névoa ativada", &mut enabled) {
+                scene.fog = enabled.then(|| Fog::exponential(scene.background(), 0.01));
+            }
+            ui.same_line();
+            // névoa baixa, que deixa ver os feixes de luz das spotlights
+            if ui.small_button("névoa de altura com feixes de luz") {
+                scene.fog = Some(Fog::height(scene.background(), 0.08, 0.0, 0.3).with_volumetric_samples(16));
+            }
+            if let Some(fog) = &mut scene.fog {
+                mod_point(ui, "Cor da névoa".to_string(), &mut fog.color, false);
+                mod_double(ui, "Densidade".to_string(), &mut fog.density);
+                mod_double(ui, "Densidade da névoa de altura".to_string(), &mut fog.height_density);
+                mod_double(ui, "Altura".to_string(), &mut fog.height);
+                mod_double(ui, "Queda com a altura".to_string(), &mut fog.height_falloff);
+                ui.input_scalar("Amostras volumétricas (spotlights)", &mut fog.volumetric_samples).build();
+            }
+        }
+
         // Menu de luzes
         let mut counter = 1;
         if ui.collapsing_header("Luzes", TreeNodeFlags::empty()) {
             let mut delete: Option<usize> = None;
             for light in &mut scene.lights {
                 match light {
-                    Light::Point { pos, intensity } => {
+                    Light::Point { pos, intensity, attenuation } => {
                         let name = format!("{counter}. LUZ PONTUAL");
                         ui.text(name);
                         mod_point(ui, format!("{counter}. Posição"), pos, true);
                         mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
+                        mod_attenuation(ui, format!("{counter}. "), attenuation);
                     }
-                    Light::Spotlight { pos, dr, angle, intensity } => {
+                    Light::Spotlight { pos, dr, angle, inner_angle, intensity, attenuation } => {
                         let name = format!("{counter}. LUZ SPOT");
                         ui.text(name);
                         mod_point(ui, format!("{counter}. Posição"), pos, true);
                         mod_dr(ui, format!("{counter}. "), dr, Some(*pos));
-                        mod_double(ui, format!("{counter}. Ângulo"), angle);
+                        mod_double(ui, format!("{counter}. Ângulo interno"), inner_angle);
+                        mod_double(ui, format!("{counter}. Ângulo externo"), angle);
                         mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
+                        mod_attenuation(ui, format!("{counter}. "), attenuation);
                     }
                     Light::Directional { dr, intensity } => {
                         let name = format!("{counter}. LUZ DIRECIONAL");
@@ -361,6 +564,32 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
                         mod_dr(ui, format!("{counter}. "), dr, None);
                         mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
                     }
+                    Light::Rectangle { pos, u, v, intensity, samples } => {
+                        let name = format!("{counter}. LUZ RETANGULAR");
+                        ui.text(name);
+                        mod_point(ui, format!("{counter}. Posição"), pos, true);
+                        mod_point(ui, format!("{counter}. Lado U"), u, false);
+                        mod_point(ui, format!("{counter}. Lado V"), v, false);
+                        mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
+                        ui.input_scalar(format!("{counter}. Amostras"), samples).build();
+                    }
+                    Light::Disk { pos, dr, radius, intensity, samples } => {
+                        let name = format!("{counter}. LUZ DE DISCO");
+                        ui.text(name);
+                        mod_point(ui, format!("{counter}. Posição"), pos, true);
+                        mod_dr(ui, format!("{counter}. "), dr, Some(*pos));
+                        mod_double(ui, format!("{counter}. Raio"), radius);
+                        mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
+                        ui.input_scalar(format!("{counter}. Amostras"), samples).build();
+                    }
+                    Light::Sphere { pos, radius, intensity, samples } => {
+                        let name = format!("{counter}. LUZ ESFÉRICA");
+                        ui.text(name);
+                        mod_point(ui, format!("{counter}. Posição"), pos, true);
+                        mod_double(ui, format!("{counter}. Raio"), radius);
+                        mod_point(ui, format!("{counter}. Intensidade"), intensity, false);
+                        ui.input_scalar(format!("{counter}. Amostras"), samples).build();
+                    }
                 }
                 if ui.button(format!("delete light {counter}")) { delete = Some(counter-1); }
                 counter += 1;
@@ -371,49 +600,3 @@ pub fn make_ui(ui: &mut Ui, scene: &mut Scene, camera: &mut Camera, selected_sha
 
     make_transformation_menu(ui);
 }
-
-fn make_day(scene: &mut Scene) {
-    let light1_direction = Vec3::new(-1.0, -2.0, 0.25).normalized();
-    let light1_color = Vec3::new(1.0, 1.0, 1.0);
-    let light1_intensity = 0.55;
-
-    let bg_color = (Vec3::new(0.35,0.63,0.95) * 255.0).clamp(0.0, 255.0); // cor do background
-    let ambient_light = Vec3::all(0.4); // Luz ambiente
-    let lights = vec![
-        Light::directional(light1_direction, light1_color, light1_intensity),
-    ];
-
-    scene.bg_color = bg_color;
-    scene.ambient_light = ambient_light;
-    scene.lights = lights;
-}
-
-pub fn make_night(scene: &mut Scene) {
-    let snowman1_x = 3.0;
-    let snowman1_z = 7.0;
-    let umbrella_top_height = 0.5;
-    let umbrella_direction = Vec3::new(0.2, 1.0, 0.0);
-    let umbrella_pole_height = 3.1;
-    let umbrella_pole_cb = Vec3::new(snowman1_x + 2.0, -0.20, snowman1_z);
-    let umbrella_top_cb = umbrella_pole_cb + umbrella_direction*(umbrella_pole_height-umbrella_top_height-0.037);
-
-    let light2_color = Vec3::new(1.0, 0.8, 0.3);
-    let light2_intensity = 0.65;
-    let light3_position = umbrella_top_cb + (umbrella_top_height * Vec3::Y * 0.9);
-    let ambient_light = Vec3::all(0.1); // Luz ambiente
-    let bg_color = Vec3::NULL;
-    
-    let lights = vec![
-        Light::spotlight(
-            Vec3::new(14.0, 6.25, 4.0),
-            -Vec3::Y, 45.0,
-            light2_color,
-            light2_intensity
-        ),
-        Light::point( light3_position, light2_color, 0.3 ),
-    ];
-
-    scene.bg_color = bg_color;
-    scene.ambient_light = ambient_light;
-    scene.lights = lights;
-}
\ No newline at end of file
@@ -0,0 +1,129 @@
+use std::error::Error;
+use super::Vec3;
+
+/// Carrega uma imagem HDR (cores lineares em ponto flutuante) de um arquivo `.hdr` (Radiance RGBE)
+/// ou `.pfm`. \
+/// Retorna as cores (linha por linha, de cima pra baixo), a largura e a altura da imagem.
+pub fn load_hdr_image(file_name: &str) -> Result<(Vec<Vec3>, u32, u32), Box<dyn Error>> {
+    let bytes = std::fs::read(file_name)?;
+    if bytes.starts_with(b"PF") || bytes.starts_with(b"Pf") {
+        read_pfm(&bytes)
+    } else if bytes.starts_with(b"#?") {
+        read_radiance_hdr(&bytes)
+    } else {
+        Err(format!("{file_name}: formato de imagem HDR desconhecido (só .hdr e .pfm)").into())
+    }
+}
+
+// Lê a próxima linha de texto do cabeçalho a partir da posição `pos`
+fn read_line<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<&'a str, Box<dyn Error>> {
+    let start = *pos;
+    let len = bytes[start..].iter().position(|&b| b == b'\n').ok_or("cabeçalho incompleto")?;
+    *pos = start + len + 1;
+    Ok(std::str::from_utf8(&bytes[start..start + len])?.trim_end_matches('\r'))
+}
+
+// .pfm: "PF" (colorido) ou "Pf" (tons de cinza), largura e altura, e a escala (negativa = little endian),
+// seguidos dos floats de 32 bits com as linhas de baixo pra cima
+fn read_pfm(bytes: &[u8]) -> Result<(Vec<Vec3>, u32, u32), Box<dyn Error>> {
+    // o cabeçalho tem 3 "palavras" depois do tipo, que podem estar em linhas diferentes
+    let mut pos = 0;
+    let mut words = Vec::new();
+    while words.len() < 4 {
+        words.extend(read_line(bytes, &mut pos)?.split_whitespace().map(str::to_string));
+    }
+    let channels = if words[0] == "PF" { 3 } else { 1 };
+    let (w, h): (u32, u32) = (words[1].parse()?, words[2].parse()?);
+    if w == 0 || h == 0 { return Err(format!("imagem .pfm vazia ({w}x{h})").into()); }
+    let little_endian = words[3].parse::<f32>()? < 0.0;
+
+    let data = &bytes[pos..];
+    let n = (w * h) as usize * channels;
+    if data.len() < n * 4 { return Err("arquivo .pfm incompleto".into()); }
+    let floats: Vec<f64> = data.chunks_exact(4).take(n).map(|b| {
+        let b = [b[0], b[1], b[2], b[3]];
+        (if little_endian { f32::from_le_bytes(b) } else { f32::from_be_bytes(b) }) as f64
+    }).collect();
+
+    let mut pixels = Vec::with_capacity((w * h) as usize);
+    for row in floats.chunks_exact(w as usize * channels).rev() {
+        for c in row.chunks_exact(channels) {
+            pixels.push(if channels == 3 { Vec3::new(c[0], c[1], c[2]) } else { Vec3::all(c[0]) });
+        }
+    }
+    Ok((pixels, w, h))
+}
+
+// .hdr do Radiance: cabeçalho de texto terminado por uma linha vazia, a resolução ("-Y altura +X largura"),
+// e os pixels em RGBE (mantissas de 8 bits com um expoente comum), normalmente com compressão RLE por linha
+fn read_radiance_hdr(bytes: &[u8]) -> Result<(Vec<Vec3>, u32, u32), Box<dyn Error>> {
+    let mut pos = 0;
+    loop {
+        let line = read_line(bytes, &mut pos)?;
+        if line.is_empty() { break; }
+        if line.starts_with("FORMAT=") && line != "FORMAT=32-bit_rle_rgbe" {
+            return Err(format!("formato .hdr não suportado: {line}").into());
+        }
+    }
+    let resolution: Vec<&str> = read_line(bytes, &mut pos)?.split_whitespace().collect();
+    let [ "-Y", h, "+X", w ] = resolution[..] else {
+        return Err("orientação da imagem .hdr não suportada (só \"-Y altura +X largura\")".into());
+    };
+    let (w, h): (u32, u32) = (w.parse()?, h.parse()?);
+    if w == 0 || h == 0 { return Err(format!("imagem .hdr vazia ({w}x{h})").into()); }
+
+    let mut pixels = Vec::with_capacity((w * h) as usize);
+    let mut scanline = vec![[0u8; 4]; w as usize];
+    for _ in 0..h {
+        read_rgbe_scanline(bytes, &mut pos, &mut scanline)?;
+        pixels.extend(scanline.iter().map(|&[r, g, b, e]| {
+            if e == 0 { return Vec3::NULL; }
+            let f = 2f64.powi(e as i32 - 136);
+            Vec3::new(r as f64 * f, g as f64 * f, b as f64 * f)
+        }));
+    }
+    Ok((pixels, w, h))
+}
+
+// Lê uma linha de pixels RGBE, que pode estar com RLE (cada canal comprimido separadamente) ou sem compressão
+fn read_rgbe_scanline(bytes: &[u8], pos: &mut usize, scanline: &mut [[u8; 4]]) -> Result<(), Box<dyn Error>> {
+    let w = scanline.len();
+    let mut next = || -> Result<u8, Box<dyn Error>> {
+        let b = *bytes.get(*pos).ok_or("arquivo .hdr incompleto")?;
+        *pos += 1;
+        Ok(b)
+    };
+    let header = [next()?, next()?, next()?, next()?];
+    let is_rle = (8..0x8000).contains(&w) && header[0] == 2 && header[1] == 2 && header[2] & 0x80 == 0;
+    if !is_rle {
+        scanline[0] = header;
+        for pixel in &mut scanline[1..] {
+            *pixel = [next()?, next()?, next()?, next()?];
+        }
+        return Ok(());
+    }
+    if ((header[2] as usize) << 8 | header[3] as usize) != w {
+        return Err("largura da linha RLE não bate com a da imagem".into());
+    }
+
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < w {
+            let count = next()? as usize;
+            if count > 128 {
+                // sequência de `count - 128` pixels com o mesmo valor
+                let count = count - 128;
+                if x + count > w { return Err("RLE inválido no arquivo .hdr".into()); }
+                let value = next()?;
+                for pixel in &mut scanline[x..x + count] { pixel[channel] = value; }
+                x += count;
+            } else {
+                // `count` valores diferentes
+                if count == 0 || x + count > w { return Err("RLE inválido no arquivo .hdr".into()); }
+                for pixel in &mut scanline[x..x + count] { pixel[channel] = next()?; }
+                x += count;
+            }
+        }
+    }
+    Ok(())
+}
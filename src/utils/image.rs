@@ -0,0 +1,57 @@
+use super::Vec3;
+
+/// Pixel RGBA com 8 bits por canal (cores em sRGB)
+pub type Rgba8 = [u8; 4];
+/// Pixel RGBA em ponto flutuante (cores lineares, sem limite de intensidade)
+pub type RgbaF32 = [f32; 4];
+
+#[derive(Clone, PartialEq)]
+/// Imagem de `width` x `height` pixels, guardados linha por linha de cima pra baixo
+pub struct Image<P> {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<P>,
+}
+
+/// Imagem de 8 bits (a imagem final, pronta pra mostrar na tela ou salvar)
+pub type ImageRgba8 = Image<Rgba8>;
+/// Imagem HDR (antes do tone mapping)
+pub type ImageF32 = Image<RgbaF32>;
+
+impl<P: Copy + Default> Image<P> {
+    #[must_use]
+    /// Cria uma imagem de `width` x `height` pixels zerados (preto e transparente)
+    pub fn new(width: u32, height: u32) -> Self {
+        Image { width, height, pixels: vec![P::default(); (width * height) as usize] }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, x: u32, y: u32) -> P {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, pixel: P) {
+        self.pixels[(y * self.width + x) as usize] = pixel;
+    }
+}
+
+impl ImageRgba8 {
+    #[inline]
+    #[must_use]
+    /// Bytes da imagem (R, G, B, A de cada pixel, linha por linha)
+    pub fn as_bytes(&self) -> &[u8] {
+        self.pixels.as_flattened()
+    }
+}
+
+impl ImageF32 {
+    #[must_use]
+    /// Cria uma imagem HDR (opaca) a partir das cores `colors` (linha por linha)
+    pub fn from_colors(width: u32, height: u32, colors: &[Vec3]) -> Self {
+        debug_assert_eq!(colors.len(), (width * height) as usize);
+        let pixels = colors.iter().map(|c| [c.x as f32, c.y as f32, c.z as f32, 1.0]).collect();
+        Image { width, height, pixels }
+    }
+}
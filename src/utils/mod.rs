@@ -3,26 +3,48 @@ mod vec3;
 mod matrix3;
 mod vec4;
 mod matrix4;
+mod rng;
+mod thread_pool;
+mod hdr_image;
+mod image;
 pub mod transform;
-use sdl2::surface::Surface;
 
 pub use vec3::Vec3;
 pub use vec4::Vec4;
 pub use matrix3::Matrix3;
 pub use matrix4::Matrix4;
+pub use rng::Rng;
+pub use thread_pool::ThreadPool;
+pub use hdr_image::load_hdr_image;
+pub use image::{Image, ImageF32, ImageRgba8, Rgba8, RgbaF32};
 
-// salva o canvas como uma imagem .ppm
-pub fn save_surface_as_ppm(surface: &Surface, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let (w, h) = (surface.width(), surface.height());
-    let pixels: Vec<u8> = surface.without_lock().unwrap().to_vec();
-    
+// salva uma imagem de 8 bits como uma imagem .ppm (sem o canal alfa)
+pub fn save_image_as_ppm(image: &ImageRgba8, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
     let mut output = String::new(); 
-    output += &format!("P3\n{} {}\n255\n", w, h); 
+    output += &format!("P3\n{} {}\n255\n", image.width, image.height); 
 
-    for i in (0..pixels.len()).step_by(4) { 
-        output += &format!("{} {} {}\n", pixels[i+2], pixels[i+1], pixels[i]);
+    for [r, g, b, _] in &image.pixels { 
+        output += &format!("{} {} {}\n", r, g, b);
     }
     std::fs::write(file_name, output)?; 
 
     Ok(())
 }
+
+// salva uma imagem HDR como uma imagem .pfm (floats de 32 bits, sem o canal alfa)
+pub fn save_hdr_as_pfm(image: &ImageF32, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    // escala negativa = little endian
+    let mut output = format!("PF\n{} {}\n-1.0\n", image.width, image.height).into_bytes();
+
+    // o .pfm guarda as linhas de baixo pra cima
+    for row in image.pixels.chunks_exact(image.width as usize).rev() {
+        for pixel in row {
+            for channel in &pixel[..3] {
+                output.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+    }
+    std::fs::write(file_name, output)?;
+
+    Ok(())
+}
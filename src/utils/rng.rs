@@ -0,0 +1,56 @@
+use super::Vec3;
+
+/// Gerador de números pseudo-aleatórios (xorshift64*). \
+/// É rápido e não depende de nenhuma biblioteca, mas não serve pra nada além de amostragem.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[inline]
+    #[must_use]
+    /// Cria um novo gerador a partir de uma seed qualquer. \
+    /// (a seed é embaralhada com splitmix64, então seeds parecidas geram sequências diferentes)
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        // o estado do xorshift nunca pode ser 0
+        Self { state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z } }
+    }
+
+    #[inline]
+    /// Retorna um u64 aleatório
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    #[inline]
+    /// Retorna um f64 aleatório no intervalo [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    #[inline]
+    /// Retorna um ponto (x, y) aleatório uniformemente distribuído num disco de raio 1
+    pub fn unit_disk(&mut self) -> (f64, f64) {
+        let r = self.next_f64().sqrt();
+        let theta = 2.0 * std::f64::consts::PI * self.next_f64();
+        (r * theta.cos(), r * theta.sin())
+    }
+
+    #[inline]
+    /// Retorna uma direção aleatória no hemisfério em volta da normal `n` (unitária),
+    /// com densidade proporcional a cos(θ) (amostragem por importância da reflexão difusa)
+    pub fn cosine_hemisphere(&mut self, n: Vec3) -> Vec3 {
+        let (u, v) = n.orthonormal_basis();
+        let (x, y) = self.unit_disk();
+        let z = (1.0 - x*x - y*y).max(0.0).sqrt();
+        x*u + y*v + z*n
+    }
+}
@@ -0,0 +1,153 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, OnceLock};
+use std::thread;
+
+type Job = dyn Fn(usize) + Sync;
+
+// Ponteiro pro trabalho atual das threads
+// (`ThreadPool::for_each` garante que ele é válido enquanto alguma thread pode usar)
+#[derive(Clone, Copy)]
+struct JobPtr(*const Job);
+unsafe impl Send for JobPtr {}
+
+struct State {
+    job: Option<JobPtr>,
+    n_jobs: usize,
+    generation: u64, // incrementado a cada trabalho novo, pras threads saberem que tem coisa pra fazer
+    finished_workers: usize,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    next_job: AtomicUsize, // fila compartilhada: cada thread pega o próximo índice livre
+    n_workers: usize,
+    new_work: Condvar,
+    workers_done: Condvar,
+}
+
+/// Conjunto de threads persistentes (criadas uma vez só) que dividem trabalhos entre si. \
+/// Cada trabalho é uma lista de `n_jobs` tarefas numeradas, e cada thread pega a próxima tarefa
+/// livre assim que termina a anterior, então as threads que pegam tarefas leves não ficam paradas.
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    run_lock: Mutex<()>, // só um trabalho roda por vez
+}
+
+impl ThreadPool {
+    #[must_use]
+    /// Cria um pool com `n_workers` threads
+    pub fn new(n_workers: usize) -> ThreadPool {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State { job: None, n_jobs: 0, generation: 0, finished_workers: 0 }),
+            next_job: AtomicUsize::new(0),
+            n_workers: n_workers.max(1),
+            new_work: Condvar::new(),
+            workers_done: Condvar::new(),
+        });
+        for i in 0..shared.n_workers {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name(format!("render-worker-{i}"))
+                .spawn(move || worker_loop(&shared))
+                .unwrap();
+        }
+        ThreadPool { shared, run_lock: Mutex::new(()) }
+    }
+
+    #[must_use]
+    /// Pool global do programa, com uma thread por núcleo do processador
+    pub fn global() -> &'static ThreadPool {
+        static POOL: OnceLock<ThreadPool> = OnceLock::new();
+        POOL.get_or_init(|| ThreadPool::new(thread::available_parallelism().map_or(4, |n| n.get())))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn n_workers(&self) -> usize {
+        self.shared.n_workers
+    }
+
+    /// Executa `job(i)` pra cada `i` em `0..n_jobs` nas threads do pool, e chama `on_done(i, resultado)`
+    /// na thread atual assim que cada tarefa termina (na ordem em que elas terminam). \
+    /// Só retorna quando todas as tarefas terminaram.
+    pub fn for_each<T: Send>(&self, n_jobs: usize, job: impl Fn(usize) -> T + Sync, mut on_done: impl FnMut(usize, T)) {
+        if n_jobs == 0 { return; }
+        let _running = self.run_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let (tx, rx) = mpsc::channel();
+        let job = |i: usize| {
+            // um panic dentro da tarefa não pode matar a thread (senão o pool travaria)
+            let result = catch_unwind(AssertUnwindSafe(|| job(i))).ok();
+            let _ = tx.send((i, result));
+        };
+        let job: &(dyn Fn(usize) + Sync) = &job;
+        // SAFETY: `WaitWorkers` espera todas as threads terminarem de usar o `job` antes dessa função
+        // retornar (inclusive se `on_done` der panic), então a referência nunca fica inválida
+        let job = JobPtr(unsafe { std::mem::transmute::<&(dyn Fn(usize) + Sync), &'static Job>(job) });
+        self.start(job, n_jobs);
+        let wait_workers = WaitWorkers(&self.shared);
+
+        let mut panicked = false;
+        for (i, result) in rx.iter().take(n_jobs) {
+            match result {
+                Some(result) => on_done(i, result),
+                None => panicked = true,
+            }
+        }
+        drop(wait_workers);
+        if panicked { panic!("uma tarefa do pool de threads entrou em pânico"); }
+    }
+
+    fn start(&self, job: JobPtr, n_jobs: usize) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.job = Some(job);
+        state.n_jobs = n_jobs;
+        state.finished_workers = 0;
+        self.shared.next_job.store(0, Ordering::SeqCst);
+        state.generation += 1;
+        self.shared.new_work.notify_all();
+    }
+}
+
+// Espera todas as threads largarem o trabalho atual quando sai de escopo
+struct WaitWorkers<'a>(&'a Shared);
+
+impl Drop for WaitWorkers<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap();
+        while state.finished_workers < self.0.n_workers {
+            state = self.0.workers_done.wait(state).unwrap();
+        }
+        state.job = None;
+    }
+}
+
+fn worker_loop(shared: &Shared) {
+    let mut seen_generation = 0;
+    loop {
+        // espera um trabalho novo
+        let (job, n_jobs) = {
+            let mut state = shared.state.lock().unwrap();
+            while state.generation == seen_generation {
+                state = shared.new_work.wait(state).unwrap();
+            }
+            seen_generation = state.generation;
+            (state.job.unwrap(), state.n_jobs)
+        };
+
+        // pega tarefas da fila até ela acabar
+        loop {
+            let i = shared.next_job.fetch_add(1, Ordering::Relaxed);
+            if i >= n_jobs { break; }
+            // SAFETY: o trabalho só é descartado depois que todas as threads avisam que terminaram (abaixo)
+            unsafe { (*job.0)(i) };
+        }
+
+        let mut state = shared.state.lock().unwrap();
+        state.finished_workers += 1;
+        if state.finished_workers == shared.n_workers {
+            shared.workers_done.notify_all();
+        }
+    }
+}
@@ -104,6 +104,22 @@ impl Vec3 {
         }
     }
     
+    #[inline]
+    #[must_use]
+    /// Converte uma cor sRGB (entre 0 e 1, como as de imagens PNG) pra espaço linear
+    pub fn srgb_to_linear(&self) -> Self {
+        let decode = |c: f64| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+        Self { x: decode(self.x), y: decode(self.y), z: decode(self.z) }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Converte uma cor linear (entre 0 e 1) pra sRGB, que é o espaço que a tela espera
+    pub fn linear_to_srgb(&self) -> Self {
+        let encode = |c: f64| if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+        Self { x: encode(self.x), y: encode(self.y), z: encode(self.z) }
+    }
+
     #[inline]
     #[must_use]
     pub fn by_transpost(&self, rhs: Self) -> Matrix3 {
@@ -151,6 +167,18 @@ impl Vec3 {
         }
     }
 
+    #[inline]
+    #[must_use]
+    /// Retorna dois vetores unitários perpendiculares ao vetor (e entre si) \
+    /// (junto com o próprio vetor normalizado, formam uma base ortonormal)
+    pub fn orthonormal_basis(&self) -> (Vec3, Vec3) {
+        let w = self.normalized();
+        let a = if w.x.abs() > 0.9 { Vec3::Y } else { Vec3::X };
+        let u = w.cross(a).normalized();
+        let v = w.cross(u);
+        (u, v)
+    }
+
     pub fn angle(&self, other: Vec3) -> f64 {
         let dot_product = self.dot(other);
         let magnitude_self = self.length();
@@ -0,0 +1,210 @@
+use aula1_rust::engine::{self, BackgroundRenderer};
+use aula1_rust::scenes;
+use aula1_rust::utils::{save_image_as_ppm, ImageRgba8, Vec3};
+use crate::cli::Options;
+use crate::user_interface::make_ui;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use std::{f64::consts::PI, time::{Duration, Instant}};
+use imgui::Context;
+use imgui_glow_renderer::{
+    glow,
+    // glow::HasContext,
+    AutoRenderer,
+};
+use imgui_sdl2_support::SdlPlatform;
+// use sdl2::video::GLProfile;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+use sdl2::video::Window;
+use sdl2::EventPump;
+
+// Create a new glow context.
+fn glow_context(window: &Window) -> glow::Context {
+    unsafe {
+        glow::Context::from_loader_function(|s| window.subsystem().gl_get_proc_address(s) as _)
+    }
+}
+
+/// Mostra as imagens da câmera na janela do SDL: copia os pixels pra uma surface do SDL
+/// (recriada quando a resolução muda) e estica ela pro tamanho da janela
+struct Presenter {
+    surface: Option<Surface<'static>>,
+}
+
+impl Presenter {
+    fn new() -> Self {
+        Presenter { surface: None }
+    }
+
+    fn present(&mut self, window: &Window, event_pump: &EventPump, image: &ImageRgba8) {
+        let surface = match &mut self.surface {
+            Some(surface) if surface.size() == (image.width, image.height) => surface,
+            // RGBA32 = bytes na ordem R, G, B, A, igual aos pixels da imagem
+            surface => surface.insert(Surface::new(image.width, image.height, PixelFormatEnum::RGBA32).unwrap()),
+        };
+        let pitch = surface.pitch() as usize;
+        let row_size = image.width as usize * 4;
+        surface.with_lock_mut(|pixels| {
+            for (dst, src) in pixels.chunks_mut(pitch).zip(image.as_bytes().chunks_exact(row_size)) {
+                dst[..row_size].copy_from_slice(src);
+            }
+        });
+
+        let mut window_surface = window.surface(event_pump).unwrap();
+        let window_rect = window_surface.rect();
+        surface.blit_scaled(surface.rect(), &mut window_surface, window_rect).unwrap();
+        window_surface.finish().unwrap();
+    }
+}
+
+/// Abre a janela com a cena escolhida em `options` e roda o loop interativo até ela ser fechada
+pub fn run(options: &Options) {
+    let (mut scene, mut camera, window_width, window_height) = scenes::by_name(&options.scene).unwrap();
+    let scale = 1.75;
+
+    // Inicializando SDL
+    let sdl_context = sdl2::init().unwrap();
+    let video_subsystem = sdl_context.video().unwrap();
+    let mut event_pump = sdl_context.event_pump().unwrap(); // cuida dos eventos como teclado mouse etc.
+    let window = video_subsystem // a janela do computador em si
+        .window("CG1 - engine", ((window_width as f64)*scale) as u32, ((window_height as f64)*scale) as u32)
+        .position_centered()
+        .resizable()
+        .opengl()
+        .build()
+        .unwrap();
+    
+    // IMGUI
+    let gl_context_gui = window.gl_create_context().unwrap();
+    // window.subsystem().gl_set_swap_interval(1).unwrap();
+
+    let gl = glow_context(&window);
+    let mut imgui = Context::create();
+    
+    imgui.set_ini_filename(None);
+    imgui.set_log_filename(None);
+    imgui.fonts()
+        .add_font(&[imgui::FontSource::DefaultFontData { config: None }]);
+    // create platform and renderer
+    let mut platform = SdlPlatform::new(&mut imgui);
+    let mut renderer = AutoRenderer::new(gl, &mut imgui).unwrap();
+    // END_IMGUI
+
+    let mut selected_shape: Option<usize> = None;
+
+    // a cena é renderizada numa thread separada, e o loop principal só mostra os frames que ficam
+    // prontos (a interface não trava enquanto um frame pesado é calculado)
+    let mut presenter = Presenter::new();
+    let mut scene_renderer = BackgroundRenderer::new();
+    let mut scene_changed = true;
+    let mut saved_first_frame = false; // o primeiro frame é salvo em output.ppm
+
+    
+    // main loop do programa
+    let mut frame_count = 0; // contador de FPS no terminal
+    let mut last_time = Instant::now();
+    'running: loop {
+        // Seção de eventos e updates
+        for event in event_pump.poll_iter() {
+            // pass all events to imgui platfrom
+            platform.handle_event(&mut imgui, &event);
+            let cdx = camera.coord_system[0];
+            // let cdy = camera.coord_system[1];
+            let cdz = camera.coord_system[2];
+            let angle_step = PI/2.0/10.0;
+            match event {
+                // muda a posição da bola em 10cm pra cada lado pelas setas do teclado
+                // setas = eixos x,y // W,S = eixo z
+                Event::KeyDown { keycode: Some(Keycode::W), .. } => { camera.translate(-0.1*cdz); } // FRONT
+                Event::KeyDown { keycode: Some(Keycode::S), .. } => { camera.translate(0.1*cdz); } // BACK
+                Event::KeyDown { keycode: Some(Keycode::A), .. } => { camera.translate(-0.1*cdx); } // LEFT
+                Event::KeyDown { keycode: Some(Keycode::D), .. } => { camera.translate(0.1*cdx); } // RIGHT
+                Event::KeyDown { keycode: Some(Keycode::SPACE), .. } => { camera.translate(0.1*Vec3::Y); } // UP
+                Event::KeyDown { keycode: Some(Keycode::LSHIFT), .. } => { camera.translate(-0.1*Vec3::Y); } // DOWN
+                Event::KeyDown { keycode: Some(Keycode::LEFT), .. } => { camera.rotate(Vec3::Y, angle_step); } // ROTATE LEFT
+                Event::KeyDown { keycode: Some(Keycode::RIGHT), .. } => { camera.rotate(Vec3::Y, -angle_step); } // ROTATE RIGHT
+                Event::KeyDown { keycode: Some(Keycode::UP), .. } => { camera.rotate(cdx, angle_step); } // ROTATE UP
+                Event::KeyDown { keycode: Some(Keycode::DOWN), .. } => { camera.rotate(cdx, -angle_step); } // ROTATE DOWN
+                Event::KeyDown { keycode: Some(Keycode::Q), .. } => { camera.rotate(cdz, angle_step); } // ROLL LEFT
+                Event::KeyDown { keycode: Some(Keycode::E), .. } => { camera.rotate(cdz, -angle_step); } // ROLL RIGHT
+                // FOV
+                Event::KeyDown { keycode: Some(Keycode::LEFTBRACKET), .. } => { camera.set_focal_distance(camera.focal_distance + 0.1); }
+                Event::KeyDown { keycode: Some(Keycode::RIGHTBRACKET), .. } => { camera.set_focal_distance(camera.focal_distance - 0.1); }
+                // PROJECTION
+                Event::KeyDown { keycode: Some(Keycode::F1), .. } => { camera.set_projection(engine::camera::Projection::Perspective); }
+                Event::KeyDown { keycode: Some(Keycode::F2), .. } => { camera.set_projection(engine::camera::Projection::Ortographic); }
+                Event::KeyDown { keycode: Some(Keycode::F3), .. } => { camera.set_projection(engine::camera::Projection::Oblique); }
+                // MOUSE CLICK
+                Event::MouseButtonDown { x, y, mouse_btn, .. } => {
+                    match mouse_btn {
+                        MouseButton::Right => {
+                            let (x,y) = (x as f64, y as f64);
+                            let (wx, wy) = window.size();
+                            let scale_x = wx as f64 / camera.viewport.cols as f64;
+                            let scale_y = wy as f64 / camera.viewport.rows as f64;
+                            if let Some((s, _, _)) = camera.send_ray((y/scale_y) as i32, (x/scale_x) as i32, &scene) {
+                                selected_shape = Some(s);
+                            }
+                        }
+                        MouseButton::Middle => {
+                            let (x,y) = (x as f64, y as f64);
+                            let (wx, wy) = window.size();
+                            let scale_x = wx as f64 / camera.viewport.cols as f64;
+                            let scale_y = wy as f64 / camera.viewport.rows as f64;
+                            if let Some((_, p, _)) = camera.send_ray((y/scale_y) as i32, (x/scale_x) as i32, &scene) {
+                                // com Ctrl só foca no ponto, sem girar a câmera
+                                if !sdl_context.keyboard().mod_state().intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                                    camera.look_at(p, Vec3::Y);
+                                }
+                                camera.focus_on(p);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                // esc pra sair do programa
+                Event::Quit{ .. } => break 'running,
+                _ => {}
+            }
+            // qualquer outra interação (teclado, cliques na interface) pode ter mudado a cena,
+            // então as amostras acumuladas não valem mais
+            if !matches!(event, Event::MouseMotion { .. }) { scene_changed = true; }
+            // println!("{:?}", camera.pos);
+        }
+        
+        // render scene
+        if scene_changed {
+            scene_renderer.submit(&scene, &camera);
+            scene_changed = false;
+        }
+        if scene_renderer.present(&mut camera) && !saved_first_frame {
+            save_image_as_ppm(&camera.image, "output.ppm").unwrap();
+            saved_first_frame = true;
+        }
+        presenter.present(&window, &event_pump, &camera.image);
+        
+        // create imgui UI
+        platform.prepare_frame(&mut imgui, &window, &event_pump);
+        let ui = imgui.new_frame();
+        make_ui(ui, &mut scene, &mut camera, &mut selected_shape);
+        // mexer em qualquer campo da interface (arrastar, digitar) pode mudar a cena ou a câmera
+        if ui.is_any_item_active() { scene_changed = true; }
+        let draw_data = imgui.render();
+        
+
+        // render UI
+        window.gl_make_current(&gl_context_gui).unwrap();
+        renderer.render(draw_data).unwrap();
+        window.gl_swap_window();
+
+        // Contador de FPS
+        frame_count += 1;
+        if last_time.elapsed() >= Duration::new(1, 0) {
+            println!("FPS: {frame_count}"); // printa o número de frames desenhados no último segundo (FPS)
+            frame_count = 0;
+            last_time = Instant::now();
+        }
+    }
+}